@@ -0,0 +1,3 @@
+-- AlterTable
+ALTER TABLE "Game" ADD COLUMN     "clockDays" INTEGER,
+ADD COLUMN     "moveDeadline" BIGINT;
@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "Game" ADD COLUMN     "clockType" TEXT NOT NULL DEFAULT 'Fischer';
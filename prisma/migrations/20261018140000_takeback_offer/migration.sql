@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "Game" ADD COLUMN     "takebackOffer" TEXT NOT NULL DEFAULT 'None';
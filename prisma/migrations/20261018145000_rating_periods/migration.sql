@@ -0,0 +1,13 @@
+-- CreateTable
+CREATE TABLE "RatingPeriod" (
+    "start" BIGINT NOT NULL,
+    "closedAt" TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT "RatingPeriod_pkey" PRIMARY KEY ("start")
+);
+
+-- AlterTable
+ALTER TABLE "Game" ADD COLUMN     "endTime" BIGINT;
+
+-- Games that ended before end times were stored are taken to have ended with their last move
+UPDATE "Game" SET "endTime" = "lastMoveTime" WHERE "status" IN ('FirstWon', 'SecondWon', 'Draw');
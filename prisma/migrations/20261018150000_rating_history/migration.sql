@@ -0,0 +1,49 @@
+-- CreateTable
+CREATE TABLE "RatingHistory" (
+    "id" TEXT NOT NULL,
+    "createdAt" TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "username" TEXT NOT NULL,
+    "gameKey" TEXT NOT NULL,
+    "gameId" TEXT NOT NULL,
+    "rating" DOUBLE PRECISION NOT NULL,
+    "rd" DOUBLE PRECISION,
+
+    CONSTRAINT "RatingHistory_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateIndex
+CREATE INDEX "RatingHistory_username_gameKey_createdAt_idx" ON "RatingHistory"("username", "gameKey", "createdAt");
+
+-- AddForeignKey
+ALTER TABLE "RatingHistory" ADD CONSTRAINT "RatingHistory_username_fkey" FOREIGN KEY ("username") REFERENCES "User"("username") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "RatingHistory" ADD CONSTRAINT "RatingHistory_gameId_fkey" FOREIGN KEY ("gameId") REFERENCES "Game"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- Backfill
+-- Games only store the ratings players started them with, so the rating after each finished rated game
+-- is the one the player started their next game of the same kind with, or their current rating after
+-- their last one. Deviations were never stored, so only the last game gets one.
+WITH "Played" AS (
+    SELECT "firstUsername" AS "username", "gameKey", "id" AS "gameId", "firstRating" AS "ratingBefore", "lastMoveTime"
+    FROM "Game"
+    WHERE "rated" AND "winType" IS NOT NULL AND "winType" <> 'Abort' AND "firstUsername" IS NOT NULL
+    UNION ALL
+    SELECT "secondUsername" AS "username", "gameKey", "id" AS "gameId", "secondRating" AS "ratingBefore", "lastMoveTime"
+    FROM "Game"
+    WHERE "rated" AND "winType" IS NOT NULL AND "winType" <> 'Abort' AND "secondUsername" IS NOT NULL
+), "Ordered" AS (
+    SELECT *, LEAD("ratingBefore") OVER (PARTITION BY "username", "gameKey" ORDER BY "lastMoveTime") AS "ratingAfter"
+    FROM "Played"
+)
+INSERT INTO "RatingHistory" ("id", "createdAt", "username", "gameKey", "gameId", "rating", "rd")
+SELECT
+    gen_random_uuid()::TEXT,
+    to_timestamp("Ordered"."lastMoveTime" / 1000.0)::TIMESTAMP(3),
+    "Ordered"."username",
+    "Ordered"."gameKey",
+    "Ordered"."gameId",
+    COALESCE("Ordered"."ratingAfter", "Perf"."rating"),
+    CASE WHEN "Ordered"."ratingAfter" IS NULL THEN "Perf"."rd" END
+FROM "Ordered"
+JOIN "Perf" ON "Perf"."username" = "Ordered"."username" AND "Perf"."gameKey" = "Ordered"."gameKey";
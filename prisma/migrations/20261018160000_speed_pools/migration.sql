@@ -0,0 +1,43 @@
+-- DropIndex
+DROP INDEX "Perf_username_gameKey_key";
+
+-- DropIndex
+DROP INDEX "RatingHistory_username_gameKey_createdAt_idx";
+
+-- AlterTable
+ALTER TABLE "Perf" ADD COLUMN     "speed" TEXT NOT NULL DEFAULT 'Bullet';
+
+-- AlterTable
+ALTER TABLE "RatingHistory" ADD COLUMN     "speed" TEXT NOT NULL DEFAULT 'Untimed';
+
+-- Seed every other speed's pool from the single perf each player had per game
+INSERT INTO "Perf" ("username", "gameKey", "speed", "rating", "rd", "volatility", "tau", "prog", "prov")
+SELECT "username", "gameKey", "speeds"."speed", "rating", "rd", "volatility", "tau", "prog", "prov"
+FROM "Perf"
+CROSS JOIN (VALUES ('Blitz'), ('Rapid'), ('Classical'), ('Correspondence'), ('Untimed')) AS "speeds"("speed");
+
+-- Put recorded ratings in the pool of the game they came from, estimating game length as the initial
+-- time plus 40 increments
+UPDATE "RatingHistory"
+SET "speed" = CASE
+    WHEN "Game"."clockDays" IS NOT NULL THEN 'Correspondence'
+    WHEN "Game"."clockInitial" IS NULL THEN 'Untimed'
+    WHEN ("Game"."clockInitial" + 40 * COALESCE("Game"."clockIncrement", 0)) / 1000 < 3 * 60 THEN 'Bullet'
+    WHEN ("Game"."clockInitial" + 40 * COALESCE("Game"."clockIncrement", 0)) / 1000 < 8 * 60 THEN 'Blitz'
+    WHEN ("Game"."clockInitial" + 40 * COALESCE("Game"."clockIncrement", 0)) / 1000 < 25 * 60 THEN 'Rapid'
+    ELSE 'Classical'
+END
+FROM "Game"
+WHERE "Game"."id" = "RatingHistory"."gameId";
+
+-- AlterTable
+ALTER TABLE "Perf" ALTER COLUMN "speed" DROP DEFAULT;
+
+-- AlterTable
+ALTER TABLE "RatingHistory" ALTER COLUMN "speed" DROP DEFAULT;
+
+-- CreateIndex
+CREATE UNIQUE INDEX "Perf_username_gameKey_speed_key" ON "Perf"("username", "gameKey", "speed");
+
+-- CreateIndex
+CREATE INDEX "RatingHistory_username_gameKey_speed_createdAt_idx" ON "RatingHistory"("username", "gameKey", "speed", "createdAt");
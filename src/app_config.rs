@@ -10,14 +10,23 @@ pub fn config_app(cfg: &mut web::ServiceConfig) {
         .service(game::join_game)
         .service(game::get_game)
         .service(game::get_lobbies)
+        .service(game::get_rating_preview)
         .service(game::add_move)
         .service(game::resign)
         .service(game::offer_draw)
         .service(game::offer_rematch)
         .service(game::send_chat)
+        .service(game::get_legal_moves)
+        .service(game::claim_game)
+        .service(game::offer_takeback)
+        .service(game::add_premove)
+        .service(game::cancel_premove)
+        .service(game::more_time)
         .service(user::create_user)
         .service(user::create_guest)
         .service(user::get_user)
+        .service(user::get_rating_history)
+        .service(user::get_leaderboard)
         .service(user::get_current_user)
         .service(user::update_profile)
         .service(user::update_preferences)
@@ -30,5 +39,6 @@ pub fn config_app(cfg: &mut web::ServiceConfig) {
         .service(user::logout)
         .service(sse::new_user_client)
         .service(sse::new_game_client)
-        .service(sse::new_lobby_client);
+        .service(sse::new_lobby_client)
+        .service(sse::pong);
 }
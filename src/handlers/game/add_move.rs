@@ -32,33 +32,79 @@ pub async fn add_move(
     let new_move: String = req.match_info().get("move").unwrap().parse().unwrap();
     let game = get_game_with_relations(&client, &game_id).await?;
 
-    // make sure it is this player's turn and that move is legal
-    let first_to_move = game.num_moves() % 2 == 0;
+    // make sure it is this player's turn
+    let first_to_move = game.first_to_move()?;
     if first_to_move && game.first_username.clone().unwrap() != username ||
-        !first_to_move && game.second_username.clone().unwrap() != username ||
-        !mill.lock().validate_move(&game, &new_move)?
+        !first_to_move && game.second_username.clone().unwrap() != username
     {
-        return Err(WebErr::Forbidden(format!("new move is invalid or not player's turn")));
+        return Err(WebErr::Forbidden(format!("not player's turn")));
     }
 
-    if game.first_time.is_some() && game.get_moves_vec().len() > 1 {
-        hourglass.lock().set_hourglass(
-            game_id.clone(),
-            if first_to_move {
-                game.second_username.clone().unwrap()
-            } else {
-                game.first_username.clone().unwrap()
-            },
-            if first_to_move {
-                game.second_time.clone().unwrap()
-            } else {
-                game.first_time.clone().unwrap()
-            },
-        );
+    let move_outcome = play_move(&client, &broadcaster, &hourglass, &mill, &player_stats, game, new_move).await?;
+
+    // a premove waiting for the player now to move is played right away, as if it was made the moment
+    // this move landed, so it spends no time on their clock. premoves that turn out to be illegal are dropped
+    if move_outcome == MoveOutcome::None {
+        let mut game = get_game_with_relations(&client, &game_id).await?;
+        let next_username = if game.first_to_move()? {
+            game.first_username.clone().unwrap()
+        } else {
+            game.second_username.clone().unwrap()
+        };
+
+        let premove = mill.lock().take_premove(&game_id, &next_username);
+        if let Some(premove) = premove {
+            if mill.lock().validate_move(&game, &premove).is_ok() {
+                game.last_move_time = time_millis();
+                if let Err(e) = play_move(&client, &broadcaster, &hourglass, &mill, &player_stats, game, premove).await {
+                    log::error!("error playing premove in game with id {}: {:?}", game_id, e);
+                }
+            }
+        }
     }
 
-    // Update the board with the new move and get the new board status
-    let move_outcome = mill.lock().update_and_check(&game, &new_move, first_to_move)?;
+    Ok(HttpResponse::Ok().json(OK_RES))
+}
+
+// validates and plays a move for the player to move, updating clocks, ratings and the stored game
+async fn play_move(
+    client: &Data<PrismaClient>,
+    broadcaster: &Data<Mutex<Broadcaster>>,
+    hourglass: &Data<Mutex<Hourglass>>,
+    mill: &Data<Mutex<LumberMill>>,
+    player_stats: &Data<Mutex<PlayerStats>>,
+    game: game::Data,
+    new_move: String,
+) -> Result<MoveOutcome, WebErr> {
+    let game_id = game.id.clone();
+
+    // make sure the move is legal
+    let first_to_move = game.first_to_move()?;
+    mill.lock().validate_move(&game, &new_move)?;
+
+    // Update the board with the new move and get the new board status, along with who moves next
+    // (players don't always alternate, e.g. after completing a box in dots and boxes)
+    let (move_outcome, next_first) = {
+        let mut mill = mill.lock();
+        let move_outcome = mill.update_and_check(&game, &new_move, first_to_move)?;
+        (move_outcome, mill.first_to_move(&game)?)
+    };
+
+    // charge the mover for the move, minus whatever of their measured network lag their quota still covers.
+    // the clock only runs once both players have moved, so nothing is spent before then
+    let elapsed = (time_millis() - game.last_move_time) as i32;
+    let lag = match game.num_moves() {
+        0 | 1 => 0,
+        _ => broadcaster.lock().take_lag_compensation(&if first_to_move {
+            game.first_username.clone().unwrap()
+        } else {
+            game.second_username.clone().unwrap()
+        }, elapsed),
+    };
+    let (ftime, stime) = (game.get_new_first_time(lag)?, game.get_new_second_time(lag)?);
+    let last_move_time = time_millis();
+    let move_deadline = game.get_new_move_deadline();
+
     let move_status = match move_outcome {
         MoveOutcome::None => GameStatus::Started,
         MoveOutcome::FirstWin => GameStatus::FirstWon,
@@ -70,8 +116,8 @@ pub async fn add_move(
 
     broadcaster.lock().game_send(&game_id, GameEvent::GameStateEvent(GameStateEvent {
         r#type: GameEventType::GameState,
-        ftime: game.get_new_first_time()?,
-        stime: game.get_new_second_time()?,
+        ftime,
+        stime,
         moves: vec![new_move.clone()],
         status: move_status,
         end_type: match move_outcome {
@@ -83,8 +129,11 @@ pub async fn add_move(
             MoveOutcome::None => Offer::from_str(&game.draw_offer)?,
             _ => Offer::None,
         },
+        takeback_offer: Offer::None,
+        undo: 0,
         frating_diff: rating_diffs.0,
         srating_diff: rating_diffs.1,
+        timestamp: time_millis(),
     }));
 
     if move_outcome != MoveOutcome::None {
@@ -96,6 +145,7 @@ pub async fn add_move(
 
         player_stats.lock().update_games(-1, &broadcaster.lock());
         mill.lock().boards.remove(&game.id);
+        hourglass.lock().break_hourglass(&game.id);
     }
 
     client
@@ -114,9 +164,11 @@ pub async fn add_move(
                     }
                     moves
                 }),
-                game::first_time::set(game.get_new_first_time()?),
-                game::second_time::set(game.get_new_second_time()?),
-                game::last_move_time::set(time_millis()),
+                game::first_time::set(ftime),
+                game::second_time::set(stime),
+                game::last_move_time::set(last_move_time),
+                game::move_deadline::set(move_deadline),
+                game::first_to_move::set(Some(next_first)),
                 game::status::set(match move_outcome {
                     MoveOutcome::None => GameStatus::Started,
                     MoveOutcome::FirstWin => GameStatus::FirstWon,
@@ -128,6 +180,8 @@ pub async fn add_move(
                     MoveOutcome::Draw => Some(EndType::Stalemate.to_string()),
                     _ => None,
                 }),
+                game::end_time::set((move_outcome != MoveOutcome::None).then_some(last_move_time)),
+                game::takeback_offer::set(Offer::None.to_string()),
                 game::draw_offer::set(match move_outcome {
                     MoveOutcome::None => game.draw_offer.clone(),
                     _ => Offer::None.to_string(),
@@ -138,5 +192,30 @@ pub async fn add_move(
         .await
         .or(Err(WebErr::Internal(format!("error updating game with id {} to add move", game_id))))?;
 
-    Ok(HttpResponse::Ok().json(OK_RES))
+    // the glass for the next player runs from when this move was saved, so it can't go off against the
+    // clock of the previous turn. correspondence games give each move a deadline. otherwise the first two
+    // moves race a deadline rather than the clock, which starts once both players have moved
+    if move_outcome == MoveOutcome::None {
+        let next_username = if next_first {
+            game.first_username.clone().unwrap()
+        } else {
+            game.second_username.clone().unwrap()
+        };
+        let elapsed = (time_millis() - last_move_time) as i32;
+        if let Some(deadline) = move_deadline {
+            hourglass.lock().set_hourglass(game_id.clone(), next_username, (deadline - time_millis()) as i32);
+        } else if game.num_moves() == 0 {
+            hourglass.lock().set_first_move_deadline(game_id.clone(), next_username, elapsed);
+        } else if game.first_time.is_some() {
+            hourglass.lock().set_hourglass(
+                game_id.clone(),
+                next_username,
+                game.get_millis_to_flag(if next_first { ftime.unwrap() } else { stime.unwrap() }, elapsed)?,
+            );
+        } else {
+            hourglass.lock().break_hourglass(&game_id);
+        }
+    }
+
+    Ok(move_outcome)
 }
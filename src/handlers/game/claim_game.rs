@@ -0,0 +1,100 @@
+use parking_lot::Mutex;
+use actix_session::Session;
+use actix_web::{post, HttpRequest, web::Data, HttpResponse};
+
+use crate::helpers::general::{get_username, set_user_playing, add_chat_alert_event, get_game_with_relations, set_user_can_start_game, time_millis};
+use crate::hourglass::Hourglass;
+use crate::models::events::{GameEvent, GameStateEvent, GameEventType, ChatAlertEvent};
+use crate::models::general::{EndType, Offer};
+use crate::player_stats::PlayerStats;
+use crate::prisma::{PrismaClient, game};
+use crate::common::WebErr;
+use crate::lumber_mill::LumberMill;
+use crate::models::res::OK_RES;
+use crate::sse::Broadcaster;
+
+
+// route for claiming victory (true) or calling a draw (false) after the opponent left the game
+#[post("/api/game/{id}/claim/{value}")]
+pub async fn claim_game(
+    req: HttpRequest,
+    client: Data<PrismaClient>,
+    session: Session,
+    broadcaster: Data<Mutex<Broadcaster>>,
+    player_stats: Data<Mutex<PlayerStats>>,
+    mill: Data<Mutex<LumberMill>>,
+    hourglass: Data<Mutex<Hourglass>>,
+) -> Result<HttpResponse, WebErr> {
+
+    let username: String = get_username(&session)?;
+    let game_id: String = req.match_info().get("id").unwrap().parse().unwrap();
+    let value: bool = req.match_info().get("value").unwrap().parse().unwrap();
+    let game = get_game_with_relations(&client, &game_id).await?.validate(&username)?;
+
+    let opponent = if game.first_username.clone().unwrap() == username {
+        game.second_username.clone().unwrap()
+    } else {
+        game.first_username.clone().unwrap()
+    };
+    if !broadcaster.lock().has_left_game(&game_id, &opponent) {
+        return Err(WebErr::Forbidden(format!("cannot claim game, {} has not left", opponent)));
+    }
+
+    let new_status = game.get_claim_game_status(&value, &username);
+    let rating_diffs = game.get_rating_diffs(new_status)?;
+
+    broadcaster.lock().game_send(&game_id, GameEvent::GameStateEvent(GameStateEvent {
+        r#type: GameEventType::GameState,
+        ftime: game.get_new_first_time(0)?,
+        stime: game.get_new_second_time(0)?,
+        moves: vec![],
+        status: new_status,
+        end_type: Some(EndType::Disconnect),
+        draw_offer: Offer::None,
+        takeback_offer: Offer::None,
+        undo: 0,
+        frating_diff: rating_diffs.0,
+        srating_diff: rating_diffs.1,
+        timestamp: time_millis(),
+    }));
+
+    let chat_alert_event = ChatAlertEvent {
+        r#type: GameEventType::ChatAlert,
+        message: if value {
+            format!("{} left the game, {} claimed victory", opponent, username)
+        } else {
+            format!("{} left the game, {} called a draw", opponent, username)
+        },
+    };
+    add_chat_alert_event(&client, &game_id, &chat_alert_event).await?;
+    broadcaster.lock().game_send(&game_id, GameEvent::ChatAlertEvent(chat_alert_event));
+
+    set_user_playing(&client, &game.first_username.clone().unwrap(), None).await?;
+    set_user_playing(&client, &game.second_username.clone().unwrap(), None).await?;
+    set_user_can_start_game(&client, &game.first_username.clone().unwrap(), true).await?;
+    set_user_can_start_game(&client, &game.second_username.clone().unwrap(), true).await?;
+    game.update_ratings(&client, new_status).await?;
+
+    player_stats.lock().update_games(-1, &broadcaster.lock());
+
+    mill.lock().boards.remove(&game.id);
+    hourglass.lock().break_hourglass(&game.id);
+    broadcaster.lock().remove_game_players(&game.id);
+
+    client
+        .game()
+        .update(
+            game::id::equals(game_id.clone()),
+            vec![
+                game::status::set(new_status.to_string()),
+                game::win_type::set(Some(EndType::Disconnect.to_string())),
+                game::end_time::set(Some(time_millis())),
+                game::draw_offer::set(Offer::None.to_string()),
+            ],
+        )
+        .exec()
+        .await
+        .or(Err(WebErr::Internal(format!("error updating game with id {} to claim", game_id))))?;
+
+    Ok(HttpResponse::Ok().json(OK_RES))
+}
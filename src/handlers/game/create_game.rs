@@ -5,6 +5,7 @@ use actix_web::{HttpRequest, HttpResponse, post};
 
 use crate::common::WebErr;
 use crate::helpers::general::{get_username, get_user_with_relations, get_game_with_relations};
+use crate::hourglass::Hourglass;
 use crate::lumber_mill::LumberMill;
 use crate::player_stats::PlayerStats;
 use crate::prisma::PrismaClient;
@@ -22,6 +23,7 @@ pub async fn create_game(
     broadcaster: Data<Mutex<Broadcaster>>,
     player_stats: Data<Mutex<PlayerStats>>,
     mill: Data<Mutex<LumberMill>>,
+    hourglass: Data<Mutex<Hourglass>>,
 ) -> Result<HttpResponse, WebErr> {
 
     let username: String = get_username(&session)?;
@@ -31,7 +33,7 @@ pub async fn create_game(
     let match_player = get_user_with_relations(&client, &username)
         .await?
         .to_match_player(&game_key, &create_game_req);
-    let game = create_game_req.create_or_join(&client, &game_key, &match_player, &broadcaster, &player_stats).await?;
+    let game = create_game_req.create_or_join(&client, &game_key, &match_player, &broadcaster, &player_stats, &hourglass).await?;
 
     mill.lock().create_board_from_game(&game)?;
 
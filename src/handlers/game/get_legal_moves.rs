@@ -0,0 +1,35 @@
+use std::str::FromStr;
+use parking_lot::Mutex;
+use actix_web::{HttpRequest, web::Data, get, HttpResponse};
+
+use crate::helpers::general::get_game_with_relations;
+use crate::lumber_mill::LumberMill;
+use crate::models::general::GameStatus;
+use crate::models::res::LegalMovesResponse;
+use crate::prisma::PrismaClient;
+use crate::common::WebErr;
+
+
+// route for getting the legal moves of the side to move in a game
+#[get("/api/game/{id}/legal-moves")]
+pub async fn get_legal_moves(
+    req: HttpRequest,
+    client: Data<PrismaClient>,
+    mill: Data<Mutex<LumberMill>>,
+) -> Result<HttpResponse, WebErr> {
+
+    let game_id: String = req.match_info().get("id").unwrap().parse().unwrap();
+    let game = get_game_with_relations(&client, &game_id).await?;
+
+    if GameStatus::from_str(&game.status)? != GameStatus::Started {
+        return Err(WebErr::BadReq(format!("game {} is not in progress", game_id)));
+    }
+
+    let mut mill = mill.lock();
+    let board = mill.get_board(&game)?;
+
+    Ok(HttpResponse::Ok().json(LegalMovesResponse {
+        moves: board.legal_moves(),
+        active_board: board.active_board(),
+    }))
+}
@@ -1,13 +1,20 @@
+use actix_session::Session;
 use actix_web::{web::Data, get, HttpResponse};
 
-use crate::helpers::general::get_unmatched_games;
+use crate::helpers::general::{get_unmatched_games, get_username, get_user_with_relations};
 use crate::prisma::PrismaClient;
 use crate::common::WebErr;
 use crate::helpers::game::LobbyVec;
 
 
-// route for getting all games
+// route for getting all games, with the rating changes at stake in each for a logged in user
 #[get("/api/lobbies")]
-pub async fn get_lobbies(client: Data<PrismaClient>) -> Result<HttpResponse, WebErr> {
-    Ok(HttpResponse::Ok().json(get_unmatched_games(&client).await?.to_lobby_vec()?))
+pub async fn get_lobbies(client: Data<PrismaClient>, session: Session) -> Result<HttpResponse, WebErr> {
+    let unmatched_games = get_unmatched_games(&client).await?;
+
+    if let Ok(username) = get_username(&session) {
+        let user = get_user_with_relations(&client, &username).await?;
+        return Ok(HttpResponse::Ok().json(unmatched_games.to_lobby_vec_with_preview(&user)?));
+    }
+    Ok(HttpResponse::Ok().json(unmatched_games.to_lobby_vec()?))
 }
@@ -0,0 +1,30 @@
+use std::str::FromStr;
+use actix_session::Session;
+use actix_web::{HttpRequest, web::Data, get, HttpResponse};
+
+use crate::helpers::general::{get_game_with_relations, get_username, get_user_with_relations};
+use crate::models::general::GameStatus;
+use crate::prisma::PrismaClient;
+use crate::common::WebErr;
+
+
+// route for getting how much the current user's rating would change by winning, drawing or losing a
+// lobby or challenge they could join. unrated games and the user's own games have nothing at stake
+#[get("/api/game/{id}/rating-preview")]
+pub async fn get_rating_preview(
+    req: HttpRequest,
+    client: Data<PrismaClient>,
+    session: Session,
+) -> Result<HttpResponse, WebErr> {
+
+    let username: String = get_username(&session)?;
+    let game_id: String = req.match_info().get("id").unwrap().parse().unwrap();
+    let game = get_game_with_relations(&client, &game_id).await?;
+
+    if GameStatus::from_str(&game.status)? != GameStatus::Waiting {
+        return Err(WebErr::BadReq(format!("game with id {} is not waiting for an opponent", game_id)));
+    }
+
+    let user = get_user_with_relations(&client, &username).await?;
+    Ok(HttpResponse::Ok().json(game.get_rating_preview(&user)?))
+}
@@ -6,6 +6,7 @@ use actix_web::{HttpRequest, HttpResponse, post};
 use crate::common::WebErr;
 use crate::helpers::general::{get_user_with_relations, get_username};
 use crate::helpers::create_game::join_game as join_game_util;
+use crate::hourglass::Hourglass;
 use crate::models::res::OK_RES;
 use crate::player_stats::PlayerStats;
 use crate::prisma::{PrismaClient, game};
@@ -20,6 +21,7 @@ pub async fn join_game(
     session: Session,
     broadcaster: Data<Mutex<Broadcaster>>,
     player_stats: Data<Mutex<PlayerStats>>,
+    hourglass: Data<Mutex<Hourglass>>,
 ) -> Result<HttpResponse, WebErr> {
 
     let username: String = get_username(&session)?;
@@ -34,9 +36,10 @@ pub async fn join_game(
         .ok_or(WebErr::NotFound(format!("could not find game with id {}", game_id)))?;
 
     let user = get_user_with_relations(&client, &username.clone()).await?;
-    let perf = user.perfs().unwrap().iter().find(|p| p.game_key == game.game_key).unwrap();
+    let rating = user.get_rating(&game.game_key, game.get_speed())?;
+    let provisional = user.get_provisional(&game.game_key, game.get_speed())?;
 
-    join_game_util(&client, &game, game.first_username.is_none(), username, perf.rating as i32, perf.prov, &broadcaster, &player_stats).await?;
+    join_game_util(&client, &game, game.first_username.is_none(), username, rating as i32, provisional, &broadcaster, &player_stats, &hourglass).await?;
 
     Ok(HttpResponse::Ok().json(OK_RES))
 }
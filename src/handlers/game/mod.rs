@@ -3,19 +3,31 @@ mod cancel_game;
 mod join_game;
 mod get_game;
 mod get_lobbies;
+mod get_rating_preview;
 mod add_move;
 mod resign;
 mod offer_draw;
 mod offer_rematch;
 mod send_chat;
+mod get_legal_moves;
+mod claim_game;
+mod offer_takeback;
+mod premove;
+mod more_time;
 
 pub use create_game::*;
 pub use cancel_game::*;
 pub use join_game::*;
 pub use get_game::*;
 pub use get_lobbies::*;
+pub use get_rating_preview::*;
 pub use add_move::*;
 pub use resign::*;
 pub use offer_draw::*;
 pub use offer_rematch::*;
 pub use send_chat::*;
+pub use get_legal_moves::*;
+pub use claim_game::*;
+pub use offer_takeback::*;
+pub use premove::*;
+pub use more_time::*;
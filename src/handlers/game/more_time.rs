@@ -0,0 +1,91 @@
+use std::str::FromStr;
+use parking_lot::Mutex;
+use actix_session::Session;
+use actix_web::{post, HttpRequest, web::Data, HttpResponse};
+
+use crate::helpers::general::{get_username, add_chat_alert_event, get_game_with_relations, time_millis};
+use crate::hourglass::Hourglass;
+use crate::models::events::{GameEvent, GameStateEvent, GameEventType, ChatAlertEvent};
+use crate::models::general::{GameStatus, Offer};
+use crate::prisma::{PrismaClient, game};
+use crate::common::WebErr;
+use crate::models::res::OK_RES;
+use crate::sse::Broadcaster;
+
+
+// Time added to the opponent's clock each time a player gives them more time
+const MORE_TIME_MILLIS: i32 = 15_000;
+
+// route for giving the opponent more time on their clock
+#[post("/api/game/{id}/moretime")]
+pub async fn more_time(
+    req: HttpRequest,
+    client: Data<PrismaClient>,
+    session: Session,
+    broadcaster: Data<Mutex<Broadcaster>>,
+    hourglass: Data<Mutex<Hourglass>>,
+) -> Result<HttpResponse, WebErr> {
+
+    let username: String = get_username(&session)?;
+    let game_id: String = req.match_info().get("id").unwrap().parse().unwrap();
+    let mut game = get_game_with_relations(&client, &game_id).await?.validate(&username)?;
+
+    if game.first_time.is_none() || game.clock_days.is_some() {
+        return Err(WebErr::BadReq(format!("cannot give more time in game with id {} without a clock", game_id)));
+    }
+    hourglass.lock().try_give_more_time(&game_id, game.rated)?;
+
+    // the stored time is what the player had when their turn started, so it can be topped up whether or not
+    // they are to move
+    let opponent_first = game.first_username.clone().unwrap() != username;
+    let (opponent, opponent_time) = if opponent_first {
+        game.first_time = game.first_time.map(|t| t + MORE_TIME_MILLIS);
+        (game.first_username.clone().unwrap(), game.first_time)
+    } else {
+        game.second_time = game.second_time.map(|t| t + MORE_TIME_MILLIS);
+        (game.second_username.clone().unwrap(), game.second_time)
+    };
+
+    // the opponent's clock is only running once both players have moved
+    if game.first_to_move()? == opponent_first && game.num_moves() >= 2 {
+        let elapsed = (time_millis() - game.last_move_time) as i32;
+        hourglass.lock().set_hourglass(game_id.clone(), opponent.clone(), game.get_millis_to_flag(opponent_time.unwrap(), elapsed)?);
+    }
+
+    broadcaster.lock().game_send(&game_id, GameEvent::GameStateEvent(GameStateEvent {
+        r#type: GameEventType::GameState,
+        ftime: game.get_new_first_time(0)?,
+        stime: game.get_new_second_time(0)?,
+        moves: vec![],
+        status: GameStatus::Started,
+        end_type: None,
+        draw_offer: Offer::from_str(&game.draw_offer)?,
+        takeback_offer: Offer::from_str(&game.takeback_offer)?,
+        undo: 0,
+        frating_diff: None,
+        srating_diff: None,
+        timestamp: time_millis(),
+    }));
+
+    let chat_alert_event = ChatAlertEvent {
+        r#type: GameEventType::ChatAlert,
+        message: format!("{} gave {} {} seconds", username, opponent, MORE_TIME_MILLIS / 1000),
+    };
+    add_chat_alert_event(&client, &game_id, &chat_alert_event).await?;
+    broadcaster.lock().game_send(&game_id, GameEvent::ChatAlertEvent(chat_alert_event));
+
+    client
+        .game()
+        .update(
+            game::id::equals(game_id.clone()),
+            vec![
+                game::first_time::set(game.first_time),
+                game::second_time::set(game.second_time),
+            ],
+        )
+        .exec()
+        .await
+        .or(Err(WebErr::Internal(format!("error updating game with id {} to give more time", game_id))))?;
+
+    Ok(HttpResponse::Ok().json(OK_RES))
+}
@@ -2,7 +2,7 @@ use parking_lot::Mutex;
 use actix_session::Session;
 use actix_web::{HttpRequest, post, web::Data, HttpResponse};
 
-use crate::helpers::general::{get_username, set_user_playing, add_chat_alert_event, get_game_with_relations, set_user_can_start_game};
+use crate::helpers::general::{get_username, set_user_playing, add_chat_alert_event, get_game_with_relations, set_user_can_start_game, time_millis};
 use crate::models::events::{GameEventType, GameStateEvent, GameEvent, ChatAlertEvent};
 use crate::models::general::Offer;
 use crate::player_stats::PlayerStats;
@@ -33,14 +33,17 @@ pub async fn offer_draw(
 
     broadcaster.lock().game_send(&game_id, GameEvent::GameStateEvent(GameStateEvent {
         r#type: GameEventType::GameState,
-        ftime: game.get_new_first_time()?,
-        stime: game.get_new_second_time()?,
+        ftime: game.get_new_first_time(0)?,
+        stime: game.get_new_second_time(0)?,
         moves: vec![],
         status: game.get_draw_game_status(&value, &username)?,
         end_type: None,
         draw_offer: game.get_new_draw_offer(&value, &username)?,
+        takeback_offer: Offer::from_str(&game.takeback_offer)?,
+        undo: 0,
         frating_diff: rating_diffs.0,
         srating_diff: rating_diffs.1,
+        timestamp: time_millis(),
     }));
 
     let chat_alert_event = ChatAlertEvent {
@@ -73,6 +76,7 @@ pub async fn offer_draw(
             vec![
                 game::status::set(game.get_draw_game_status(&value, &username)?.to_string()),
                 game::draw_offer::set(game.get_new_draw_offer(&value, &username)?.to_string()),
+                game::end_time::set((game.get_new_draw_offer(&value, &username)? == Offer::Agreed).then(time_millis)),
             ],
         )
         .exec()
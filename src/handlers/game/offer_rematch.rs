@@ -6,6 +6,7 @@ use actix_web::{HttpRequest, HttpResponse, post};
 
 use crate::common::WebErr;
 use crate::helpers::general::{get_username, set_user_playing, gen_nanoid, add_chat_alert_event, get_game_with_relations, set_user_can_start_game};
+use crate::hourglass::Hourglass;
 use crate::lumber_mill::LumberMill;
 use crate::models::events::{GameEvent, GameEventType, RematchEvent, ChatAlertEvent};
 use crate::models::general::{Offer, GameStatus};
@@ -23,7 +24,8 @@ pub async fn offer_rematch(
     session: Session,
     broadcaster: Data<Mutex<Broadcaster>>,
     player_stats: Data<Mutex<PlayerStats>>,
-    mill: Data<Mutex<LumberMill>>
+    mill: Data<Mutex<LumberMill>>,
+    hourglass: Data<Mutex<Hourglass>>,
 ) -> Result<HttpResponse, WebErr> {
 
     let username: String = get_username(&session)?;
@@ -47,7 +49,7 @@ pub async fn offer_rematch(
     if new_rematch_offer == Offer::Agreed {
         let id = gen_nanoid(&client).await;
 
-        client
+        let rematch = client
             .game()
             .create(
                 id.clone(),
@@ -64,12 +66,15 @@ pub async fn offer_rematch(
                 vec![
                     game::clock_initial::set(game.clock_initial),
                     game::clock_increment::set(game.clock_increment),
+                    game::clock_days::set(game.clock_days),
+                    game::clock_type::set(game.clock_type.clone()),
+                    game::move_deadline::set(game.get_new_move_deadline()),
                     game::first_time::set(game.clock_initial),
                     game::second_time::set(game.clock_initial),
-                    game::first_rating::set(Some(game.second_user().unwrap().unwrap().get_rating(&game.game_key)? as i32)),
-                    game::second_rating::set(Some(game.first_user().unwrap().unwrap().get_rating(&game.game_key)? as i32)),
-                    game::first_prov::set(Some(game.second_user().unwrap().unwrap().get_provisional(&game.game_key)?)),
-                    game::second_prov::set(Some(game.first_user().unwrap().unwrap().get_provisional(&game.game_key)?)),
+                    game::first_rating::set(Some(game.second_user().unwrap().unwrap().get_rating(&game.game_key, game.get_speed())? as i32)),
+                    game::second_rating::set(Some(game.first_user().unwrap().unwrap().get_rating(&game.game_key, game.get_speed())? as i32)),
+                    game::first_prov::set(Some(game.second_user().unwrap().unwrap().get_provisional(&game.game_key, game.get_speed())?)),
+                    game::second_prov::set(Some(game.first_user().unwrap().unwrap().get_provisional(&game.game_key, game.get_speed())?)),
                     game::start_pos::set(game.start_pos.clone()),
                     game::first_user::connect(user::username::equals(game.second_username.clone().unwrap())),
                     game::second_user::connect(user::username::equals(game.first_username.clone().unwrap())),
@@ -85,6 +90,7 @@ pub async fn offer_rematch(
         set_user_can_start_game(&client, &game.second_username.clone().unwrap(), false).await?;
 
         player_stats.lock().update_games(1, &broadcaster.lock());
+        hourglass.lock().start_turn(&rematch)?;
 
         mill.lock().create_board_from_game(&game)?;
 
@@ -0,0 +1,144 @@
+use std::str::FromStr;
+use parking_lot::Mutex;
+use actix_session::Session;
+use actix_web::{HttpRequest, post, web::Data, HttpResponse};
+
+use crate::helpers::general::{get_username, add_chat_alert_event, get_game_with_relations, time_millis};
+use crate::hourglass::Hourglass;
+use crate::models::events::{GameEventType, GameStateEvent, GameEvent, ChatAlertEvent};
+use crate::models::general::{Offer, GameStatus};
+use crate::prisma::{PrismaClient, game};
+use crate::common::WebErr;
+use crate::lumber_mill::LumberMill;
+use crate::models::res::OK_RES;
+use crate::sse::Broadcaster;
+
+
+// route for offering, accepting or declining a takeback of the offering player's last move
+#[post("/api/game/{id}/takeback/{value}")]
+pub async fn offer_takeback(
+    req: HttpRequest,
+    client: Data<PrismaClient>,
+    session: Session,
+    broadcaster: Data<Mutex<Broadcaster>>,
+    mill: Data<Mutex<LumberMill>>,
+    hourglass: Data<Mutex<Hourglass>>,
+) -> Result<HttpResponse, WebErr> {
+
+    let username: String = get_username(&session)?;
+    let game_id: String = req.match_info().get("id").unwrap().parse().unwrap();
+    let value: bool = req.match_info().get("value").unwrap().parse().unwrap();
+    let game = get_game_with_relations(&client, &game_id).await?.validate(&username)?;
+
+    let new_takeback_offer = game.get_new_takeback_offer(&value, &username)?;
+
+    // the player whose last move would be taken back, either the one offering or the one whose offer was accepted
+    let offering_first = match new_takeback_offer {
+        Offer::First => Some(true),
+        Offer::Second => Some(false),
+        Offer::Agreed => Some(game.first_username.clone().unwrap() != username),
+        Offer::None => None,
+    };
+    let undo = match offering_first {
+        Some(first) => game.get_takeback_moves(first)?.ok_or(WebErr::BadReq(format!("no move to take back")))?,
+        None => 0,
+    };
+
+    let chat_alert_event = ChatAlertEvent {
+        r#type: GameEventType::ChatAlert,
+        message: match new_takeback_offer {
+            Offer::None => format!("{} declined the takeback", username),
+            Offer::First | Offer::Second => format!("{} asked for a takeback", username),
+            Offer::Agreed => format!("{} accepted the takeback", username),
+        },
+    };
+
+    if new_takeback_offer != Offer::Agreed {
+        broadcaster.lock().game_send(&game_id, GameEvent::GameStateEvent(GameStateEvent {
+            r#type: GameEventType::GameState,
+            ftime: game.get_new_first_time(0)?,
+            stime: game.get_new_second_time(0)?,
+            moves: vec![],
+            status: GameStatus::Started,
+            end_type: None,
+            draw_offer: Offer::from_str(&game.draw_offer)?,
+            takeback_offer: new_takeback_offer,
+            undo: 0,
+            frating_diff: None,
+            srating_diff: None,
+            timestamp: time_millis(),
+        }));
+
+        add_chat_alert_event(&client, &game_id, &chat_alert_event).await?;
+        broadcaster.lock().game_send(&game_id, GameEvent::ChatAlertEvent(chat_alert_event));
+
+        client
+            .game()
+            .update(
+                game::id::equals(game_id.clone()),
+                vec![
+                    game::takeback_offer::set(new_takeback_offer.to_string()),
+                ],
+            )
+            .exec()
+            .await
+            .or(Err(WebErr::Internal(format!("error updating game with id {} to offer takeback", game_id))))?;
+
+        return Ok(HttpResponse::Ok().json(OK_RES));
+    }
+
+    // stop the clock of the player to move where it is, and start the turn of the player taking back
+    // their move from now
+    let mut taken_back = game.clone();
+    let mut moves = game.get_moves_vec();
+    moves.truncate(moves.len() - undo);
+    taken_back.moves = moves.join(" ");
+    taken_back.first_time = game.get_first_time_left()?;
+    taken_back.second_time = game.get_second_time_left()?;
+    taken_back.last_move_time = time_millis();
+    taken_back.move_deadline = game.get_new_move_deadline();
+    taken_back.takeback_offer = Offer::None.to_string();
+
+    mill.lock().create_board_from_game(&taken_back)?;
+    taken_back.first_to_move = Some(mill.lock().first_to_move(&taken_back)?);
+    mill.lock().clear_premove(&game_id);
+    hourglass.lock().start_turn(&taken_back)?;
+
+    broadcaster.lock().game_send(&game_id, GameEvent::GameStateEvent(GameStateEvent {
+        r#type: GameEventType::GameState,
+        ftime: taken_back.first_time,
+        stime: taken_back.second_time,
+        moves: vec![],
+        status: GameStatus::Started,
+        end_type: None,
+        draw_offer: Offer::from_str(&game.draw_offer)?,
+        takeback_offer: Offer::None,
+        undo,
+        frating_diff: None,
+        srating_diff: None,
+        timestamp: time_millis(),
+    }));
+
+    add_chat_alert_event(&client, &game_id, &chat_alert_event).await?;
+    broadcaster.lock().game_send(&game_id, GameEvent::ChatAlertEvent(chat_alert_event));
+
+    client
+        .game()
+        .update(
+            game::id::equals(game_id.clone()),
+            vec![
+                game::moves::set(taken_back.moves.clone()),
+                game::first_time::set(taken_back.first_time),
+                game::second_time::set(taken_back.second_time),
+                game::last_move_time::set(taken_back.last_move_time),
+                game::move_deadline::set(taken_back.move_deadline),
+                game::first_to_move::set(taken_back.first_to_move),
+                game::takeback_offer::set(Offer::None.to_string()),
+            ],
+        )
+        .exec()
+        .await
+        .or(Err(WebErr::Internal(format!("error updating game with id {} to take back moves", game_id))))?;
+
+    Ok(HttpResponse::Ok().json(OK_RES))
+}
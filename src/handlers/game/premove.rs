@@ -0,0 +1,52 @@
+use parking_lot::Mutex;
+use actix_session::Session;
+use actix_web::{post, HttpRequest, web::Data, HttpResponse};
+
+use crate::helpers::general::{get_username, get_game_by_id};
+use crate::prisma::PrismaClient;
+use crate::common::WebErr;
+use crate::lumber_mill::LumberMill;
+use crate::models::res::OK_RES;
+
+
+// route for queuing a move to be played as soon as the opponent moves
+#[post("/api/game/{id}/premove/{move}")]
+pub async fn add_premove(
+    req: HttpRequest,
+    client: Data<PrismaClient>,
+    session: Session,
+    mill: Data<Mutex<LumberMill>>,
+) -> Result<HttpResponse, WebErr> {
+
+    let username: String = get_username(&session)?;
+    let game_id: String = req.match_info().get("id").unwrap().parse().unwrap();
+    let new_move: String = req.match_info().get("move").unwrap().parse().unwrap();
+    let game = get_game_by_id(&client, &game_id).await?.validate(&username)?;
+
+    // make sure it is not this player's turn, since they can just move
+    let first_to_move = mill.lock().first_to_move(&game)?;
+    if first_to_move == (game.first_username.clone().unwrap() == username) {
+        return Err(WebErr::Forbidden(format!("cannot premove on player's own turn")));
+    }
+    mill.lock().set_premove(&game_id, &username, &new_move)?;
+
+    Ok(HttpResponse::Ok().json(OK_RES))
+}
+
+// route for cancelling a queued move
+#[post("/api/game/{id}/cancel-premove")]
+pub async fn cancel_premove(
+    req: HttpRequest,
+    client: Data<PrismaClient>,
+    session: Session,
+    mill: Data<Mutex<LumberMill>>,
+) -> Result<HttpResponse, WebErr> {
+
+    let username: String = get_username(&session)?;
+    let game_id: String = req.match_info().get("id").unwrap().parse().unwrap();
+    get_game_by_id(&client, &game_id).await?.validate(&username)?;
+
+    mill.lock().take_premove(&game_id, &username);
+
+    Ok(HttpResponse::Ok().json(OK_RES))
+}
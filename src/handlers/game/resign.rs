@@ -2,7 +2,7 @@ use parking_lot::Mutex;
 use actix_session::Session;
 use actix_web::{post, HttpRequest, web::Data, HttpResponse};
 
-use crate::helpers::general::{get_username, set_user_playing, add_chat_alert_event, get_game_with_relations, set_user_can_start_game};
+use crate::helpers::general::{get_username, set_user_playing, add_chat_alert_event, get_game_with_relations, set_user_can_start_game, time_millis};
 use crate::models::events::{GameEvent, GameStateEvent, GameEventType, ChatAlertEvent};
 use crate::models::general::{EndType, Offer};
 use crate::player_stats::PlayerStats;
@@ -32,14 +32,17 @@ pub async fn resign(
 
     broadcaster.lock().game_send(&game_id, GameEvent::GameStateEvent(GameStateEvent {
         r#type: GameEventType::GameState,
-        ftime: game.get_new_first_time()?,
-        stime: game.get_new_second_time()?,
+        ftime: game.get_new_first_time(0)?,
+        stime: game.get_new_second_time(0)?,
         moves: vec![],
         status: game.get_resign_game_status(&username),
         end_type: Some(EndType::Resign),
         draw_offer: Offer::None,
+        takeback_offer: Offer::None,
+        undo: 0,
         frating_diff: rating_diffs.0,
         srating_diff: rating_diffs.1,
+        timestamp: time_millis(),
     }));
 
     let chat_alert_event = ChatAlertEvent {
@@ -66,6 +69,7 @@ pub async fn resign(
             vec![
                 game::status::set(game.get_resign_game_status(&username).to_string()),
                 game::win_type::set(Some(EndType::Resign.to_string())),
+                game::end_time::set(Some(time_millis())),
                 game::draw_offer::set(Offer::None.to_string()),
             ],
         )
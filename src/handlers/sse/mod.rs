@@ -1,7 +1,9 @@
 mod new_user_client;
 mod new_game_client;
 mod new_lobby_client;
+mod pong;
 
 pub use new_user_client::*;
 pub use new_game_client::*;
 pub use new_lobby_client::*;
+pub use pong::*;
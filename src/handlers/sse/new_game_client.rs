@@ -1,10 +1,11 @@
 use std::str::FromStr;
 use parking_lot::Mutex;
+use actix_session::Session;
 use actix_web::web::Data;
 use actix_web::{HttpResponse, get, HttpRequest};
 
 use crate::common::WebErr;
-use crate::helpers::general::get_game_with_relations;
+use crate::helpers::general::{get_game_with_relations, get_username};
 use crate::models::events::{GameEvent, Event};
 use crate::models::general::GameStatus;
 use crate::prisma::PrismaClient;
@@ -15,6 +16,7 @@ use crate::sse::Broadcaster;
 #[get("/api/game/{id}/events")]
 pub async fn new_game_client(
     req: HttpRequest,
+    session: Session,
     client: Data<PrismaClient>,
     broadcaster: Data<Mutex<Broadcaster>>,
 ) -> Result<HttpResponse, WebErr> {
@@ -27,6 +29,16 @@ pub async fn new_game_client(
         return Err(WebErr::Forbidden(format!("cannot fetch event stream, game has not started yet")));
     }
 
+    // spectators don't need a session, but players' streams are tracked so their opponent can claim
+    // the game if they leave
+    if let Ok(username) = get_username(&session) {
+        if GameStatus::from_str(&game.status)? == GameStatus::Started
+            && (game.first_username == Some(username.clone()) || game.second_username == Some(username.clone()))
+        {
+            broadcaster.lock().add_player_client(game_id.clone(), username, tx.clone());
+        }
+    }
+
     broadcaster.lock().send_single(&tx, Event::GameEvent(
         GameEvent::GameFullEvent(game.to_game_full_event()?)
     ));
@@ -0,0 +1,26 @@
+use parking_lot::Mutex;
+use actix_session::Session;
+use actix_web::{HttpRequest, HttpResponse, post, web::Data};
+
+use crate::common::WebErr;
+use crate::helpers::general::get_username;
+use crate::models::res::OK_RES;
+use crate::sse::Broadcaster;
+
+
+// route for answering a user event stream ping, echoing the time it was sent to measure the user's lag
+#[post("/api/events/pong/{sent_at}")]
+pub async fn pong(
+    req: HttpRequest,
+    session: Session,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, WebErr> {
+
+    let username: String = get_username(&session)?;
+    let sent_at: i64 = req.match_info().get("sent_at").unwrap().parse()
+        .or(Err(WebErr::BadReq(format!("invalid ping time"))))?;
+
+    broadcaster.lock().record_round_trip(&username, sent_at);
+
+    Ok(HttpResponse::Ok().json(OK_RES))
+}
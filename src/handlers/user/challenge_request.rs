@@ -4,10 +4,11 @@ use actix_web::web::{Data, Json};
 use actix_web::{HttpResponse, HttpRequest, post};
 
 use crate::common::WebErr;
-use crate::helpers::create_game::join_game;
+use crate::helpers::create_game::{join_game, validate_start_pos, validate_time_control};
 use crate::helpers::general::{get_username, gen_nanoid, get_user_with_relations, set_user_can_start_game};
+use crate::hourglass::Hourglass;
 use crate::models::events::{UserEvent, UserEventType, ChallengeEvent, ChallengeDeclinedEvent, ChallengeCanceledEvent};
-use crate::models::general::{Offer, GameStatus, Side, GameKey, GameType, TimeControl, Challenge};
+use crate::models::general::{Offer, GameStatus, Side, GameKey, GameType, TimeControl, Challenge, Speed};
 use crate::models::req::ChallengeReq;
 use crate::models::res::OK_RES;
 use crate::player_stats::PlayerStats;
@@ -24,6 +25,7 @@ pub async fn challenge_request(
     data: Option<Json<ChallengeReq>>,
     broadcaster: Data<Mutex<Broadcaster>>,
     player_stats: Data<Mutex<PlayerStats>>,
+    hourglass: Data<Mutex<Hourglass>>,
 ) -> Result<HttpResponse, WebErr> {
 
     let username: String = get_username(&session)?;
@@ -84,8 +86,9 @@ pub async fn challenge_request(
                 .or(Err(WebErr::Internal(format!("error fetching challenge game with id {}", existing.game_id))))?
                 .ok_or(WebErr::NotFound(format!("could not find challenge game with id {}", existing.game_id)))?;
 
-            let perf = user.perfs().unwrap().iter().find(|p| p.game_key == game.game_key.to_string()).unwrap();
-            join_game(&client, &game, game.first_username.is_none(), username.clone(), perf.rating as i32, perf.prov, &broadcaster, &player_stats).await?;
+            let rating = user.get_rating(&game.game_key, game.get_speed())?;
+            let provisional = user.get_provisional(&game.game_key, game.get_speed())?;
+            join_game(&client, &game, game.first_username.is_none(), username.clone(), rating as i32, provisional, &broadcaster, &player_stats, &hourglass).await?;
         } else {
             // Decline the challenge if the `opponent` sends `false`
             client
@@ -114,6 +117,9 @@ pub async fn challenge_request(
     let challenge_req = data
         .unwrap_or(Err(WebErr::BadReq(format!("new challenge request missing json body")))?)
         .into_inner();
+    validate_start_pos(&challenge_req.game_key.to_string(), &challenge_req.start_pos)?;
+    validate_time_control(challenge_req.time, challenge_req.increment, challenge_req.days)?;
+    let speed = Speed::from_clock(challenge_req.time, challenge_req.increment, challenge_req.days);
     let game_id = gen_nanoid(&client).await;
 
     let game = client
@@ -133,6 +139,8 @@ pub async fn challenge_request(
             vec![
                 game::clock_initial::set(challenge_req.time),
                 game::clock_increment::set(challenge_req.increment),
+                game::clock_days::set(challenge_req.days),
+                game::clock_type::set(challenge_req.clock_type.to_string()),
                 game::first_time::set(challenge_req.time),
                 game::second_time::set(challenge_req.time),
                 game::start_pos::set(challenge_req.start_pos.clone()),
@@ -142,14 +150,14 @@ pub async fn challenge_request(
                     game::second_user::connect(user::username::equals(username.clone()))
                 },
                 if challenge_req.side == Side::First {
-                    game::first_rating::set(Some(user.get_rating(&challenge_req.game_key.to_string())? as i32))
+                    game::first_rating::set(Some(user.get_rating(&challenge_req.game_key.to_string(), speed)? as i32))
                 } else {
-                    game::second_rating::set(Some(user.get_rating(&challenge_req.game_key.to_string())? as i32))
+                    game::second_rating::set(Some(user.get_rating(&challenge_req.game_key.to_string(), speed)? as i32))
                 },
                 if challenge_req.side == Side::First {
-                    game::first_prov::set(Some(user.get_provisional(&challenge_req.game_key.to_string())?))
+                    game::first_prov::set(Some(user.get_provisional(&challenge_req.game_key.to_string(), speed)?))
                 } else {
-                    game::second_prov::set(Some(user.get_provisional(&challenge_req.game_key.to_string())?))
+                    game::second_prov::set(Some(user.get_provisional(&challenge_req.game_key.to_string(), speed)?))
                 },
             ],
         )
@@ -174,7 +182,7 @@ pub async fn challenge_request(
     broadcaster.lock().user_send(&opponent.clone(), UserEvent::ChallengeEvent(ChallengeEvent {
         r#type: UserEventType::Challenge,
         challenge: Challenge {
-            user: user.to_player(&challenge_req.game_key.to_string())?,
+            user: user.to_player(&challenge_req.game_key.to_string(), speed)?,
             game: GameType {
                 key: challenge_req.game_key.to_string(),
                 name: GameKey::get_game_name(&challenge_req.game_key.to_string())?,
@@ -185,6 +193,8 @@ pub async fn challenge_request(
             time_control: TimeControl {
                 initial: challenge_req.time,
                 increment: challenge_req.increment,
+                days: challenge_req.days,
+                clock_type: challenge_req.clock_type,
             },
             created_at: challenge.created_at.to_string(),
         },
@@ -0,0 +1,25 @@
+use std::str::FromStr;
+use parking_lot::Mutex;
+use actix_web::{web, HttpRequest, HttpResponse, get};
+
+use crate::common::WebErr;
+use crate::leaderboard::Leaderboards;
+use crate::models::general::GameKey;
+use crate::models::req::LeaderboardQuery;
+
+
+// route for getting the top rated players of a game, optionally in one speed's rating pool, only those
+// from one country, and only the first few
+#[get("/api/leaderboard/{game}")]
+pub async fn get_leaderboard(
+    req: HttpRequest,
+    leaderboards: web::Data<Mutex<Leaderboards>>,
+    query: web::Query<LeaderboardQuery>,
+) -> Result<HttpResponse, WebErr> {
+
+    let game_key: String = req.match_info().get("game").unwrap().parse().unwrap();
+    GameKey::from_str(&game_key).or(Err(WebErr::BadReq(format!("invalid game key {}", game_key))))?;
+
+    let query = query.into_inner();
+    Ok(HttpResponse::Ok().json(leaderboards.lock().get_leaderboard(&game_key, query.speed, query.country, query.limit)))
+}
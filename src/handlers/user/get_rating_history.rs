@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use actix_web::{web, HttpRequest, HttpResponse, get};
+
+use crate::common::WebErr;
+use crate::models::general::{GameKey, HistoryBucket, Speed};
+use crate::models::req::RatingHistoryQuery;
+use crate::models::res::RatingHistoryPoint;
+use crate::prisma::{PrismaClient, rating_history, SortOrder};
+
+
+// route for getting a user's rating after each rated game of a kind, or after the last game of each
+// day, week or month when bucketed, in every speed's rating pool or only the given one
+#[get("/api/user/{username}/rating-history/{game}")]
+pub async fn get_rating_history(
+    req: HttpRequest,
+    client: web::Data<PrismaClient>,
+    query: web::Query<RatingHistoryQuery>,
+) -> Result<HttpResponse, WebErr> {
+
+    let username: String = req.match_info().get("username").unwrap().parse().unwrap();
+    let game_key: String = req.match_info().get("game").unwrap().parse().unwrap();
+    GameKey::from_str(&game_key).or(Err(WebErr::BadReq(format!("invalid game key {}", game_key))))?;
+
+    let mut filters = vec![
+        rating_history::username::equals(username.clone()),
+        rating_history::game_key::equals(game_key.clone()),
+    ];
+    if let Some(speed) = query.speed {
+        filters.push(rating_history::speed::equals(speed.to_string()));
+    }
+
+    let history = client
+        .rating_history()
+        .find_many(filters)
+        .order_by(rating_history::created_at::order(SortOrder::Asc))
+        .exec()
+        .await
+        .or(Err(WebErr::Internal(format!("error fetching {} rating history for user {}", game_key, username))))?;
+
+    let bucket_format = query.bucket.map(|bucket| match bucket {
+        HistoryBucket::Day => "%Y-%m-%d",
+        HistoryBucket::Week => "%G-%V",
+        HistoryBucket::Month => "%Y-%m",
+    });
+
+    // points replace the earlier point of the same speed in their bucket, as history is in order
+    let mut points: Vec<RatingHistoryPoint> = vec![];
+    let mut bucket_points: HashMap<(String, String), usize> = HashMap::new();
+    for h in history {
+        let point = RatingHistoryPoint {
+            rating: h.rating,
+            rd: h.rd,
+            speed: Speed::from_str(&h.speed)?,
+            game_id: h.game_id,
+            created_at: h.created_at.to_string(),
+        };
+
+        if let Some(format) = bucket_format {
+            let bucket = (h.speed.clone(), h.created_at.format(format).to_string());
+            if let Some(&i) = bucket_points.get(&bucket) {
+                points[i] = point;
+                continue;
+            }
+            bucket_points.insert(bucket, points.len());
+        }
+        points.push(point);
+    }
+
+    Ok(HttpResponse::Ok().json(points))
+}
@@ -1,6 +1,8 @@
 mod create_user;
 mod create_guest;
 mod get_user;
+mod get_rating_history;
+mod get_leaderboard;
 mod get_current_user;
 mod update_profile;
 mod update_preferences;
@@ -15,6 +17,8 @@ mod logout;
 pub use create_user::*;
 pub use create_guest::*;
 pub use get_user::*;
+pub use get_rating_history::*;
+pub use get_leaderboard::*;
 pub use get_current_user::*;
 pub use update_profile::*;
 pub use update_preferences::*;
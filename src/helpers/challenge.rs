@@ -1,6 +1,6 @@
 use crate::common::WebErr;
 use crate::prisma::challenge;
-use crate::models::general::{Challenge, GameKey, GameType, TimeControl, Side};
+use crate::models::general::{Challenge, GameKey, GameType, Side};
 
 
 impl challenge::Data {
@@ -9,17 +9,14 @@ impl challenge::Data {
         let user = self.user().or(Err(WebErr::Internal(format!("user relation not fetched"))))?;
 
         Ok(Challenge {
-            user: user.to_player(game.game_key.as_str())?,
+            user: user.to_player(game.game_key.as_str(), game.get_speed())?,
             id: self.game_id.clone(),
             rated: game.rated,
             game: GameType {
                 key: game.game_key.clone(),
                 name: GameKey::get_game_name(&game.game_key)?,
             },
-            time_control: TimeControl {
-                initial: game.clock_initial,
-                increment: game.clock_increment,
-            },
+            time_control: game.to_time_control(),
             side: if game.random_side {
                 Side::Random
             } else if game.first_username.is_some() {
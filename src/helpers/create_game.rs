@@ -1,19 +1,31 @@
 use std::env;
+use std::str::FromStr;
 use parking_lot::Mutex;
 use actix_web::web;
 
 use crate::common::WebErr;
+use crate::hourglass::Hourglass;
 use crate::models::events::{UserEvent, GameStartEvent, UserEventType};
-use crate::models::general::{GameStatus, MatchPlayer, GameKey, Offer};
+use crate::models::general::{GameStatus, MatchPlayer, GameKey, Offer, Speed};
 use crate::models::req::CreateGameReq;
 use crate::player_stats::PlayerStats;
 use crate::prisma::PrismaClient;
 use crate::prisma::{game, user};
 use crate::sse::Broadcaster;
 use super::general::{set_user_playing, send_lobby_event, gen_nanoid, set_user_can_start_game};
+use super::moves::rules::create_board;
+
+
+// Most days per move a correspondence game can have
+const MAX_CLOCK_DAYS: i32 = 14;
 
 
 impl CreateGameReq {
+    // gets the rating pool a game created from this request is played in
+    pub fn get_speed(&self) -> Speed {
+        Speed::from_clock(self.time, self.increment, self.days)
+    }
+
     // method to validate this game request
     pub async fn validate(
         &self, 
@@ -42,8 +54,11 @@ impl CreateGameReq {
         player: &MatchPlayer,
         broadcaster: &web::Data<Mutex<Broadcaster>>,
         player_stats: &web::Data<Mutex<PlayerStats>>,
+        hourglass: &web::Data<Mutex<Hourglass>>,
     ) -> Result<game::Data, WebErr> {
 
+        validate_start_pos(game_key, &self.start_pos)?;
+        validate_time_control(self.time, self.increment, self.days)?;
         if !self.validate(client, player).await? {
             return Err(WebErr::Forbidden(format!("user {} does not meet requirements to create or join this game", player.username)));
         }
@@ -51,7 +66,7 @@ impl CreateGameReq {
         // Try to find a game match; if found, join it. Otherwise, create a new game from the req.
         Ok(match self.find_match(client, game_key, player).await? {
             Some(g) =>
-                join_game(client, &g, player.first, player.username.clone(), player.rating as i32, player.provisional, broadcaster, player_stats).await?,
+                join_game(client, &g, player.first, player.username.clone(), player.rating as i32, player.provisional, broadcaster, player_stats, hourglass).await?,
             None =>
                 self.create_game(client, game_key, player, broadcaster).await?,
         })
@@ -84,6 +99,8 @@ impl CreateGameReq {
                 vec![
                     game::clock_initial::set(self.time),
                     game::clock_increment::set(self.increment),
+                    game::clock_days::set(self.days),
+                    game::clock_type::set(self.clock_type.to_string()),
                     game::first_time::set(self.time),
                     game::second_time::set(self.time),
                     game::start_pos::set(self.start_pos.clone()),
@@ -128,6 +145,9 @@ impl CreateGameReq {
                 game::game_key::equals(game_key.to_string()),
                 game::clock_initial::equals(self.time),
                 game::clock_increment::equals(self.increment),
+                game::clock_days::equals(self.days),
+                game::clock_type::equals(self.clock_type.to_string()),
+                game::start_pos::equals(self.start_pos.clone()),
                 if player.first {
                     game::first_username::equals(None)
                 } else {
@@ -139,6 +159,8 @@ impl CreateGameReq {
             .await
             .or(Err(WebErr::Internal(format!("error fetching games"))))?;
 
+        // games with the same clock are in the same rating pool, so the player's rating (from the pool of
+        // this request's speed) can be compared with the waiting player's
         let filtered_games = games.iter().filter(|g| {
             let rating = match g.first_rating {
                 Some(r) => r,
@@ -157,6 +179,26 @@ impl CreateGameReq {
     }
 }
 
+// Asserts that a custom start position can be played in the given game, returning a
+// `WebErr::BadReq` with the reason if it cannot. An empty start position means the default board,
+// which games that need a start position (like m,n,k games) don't have.
+pub fn validate_start_pos(game_key: &str, start_pos: &Option<String>) -> Result<(), WebErr> {
+    create_board(&GameKey::from_str(game_key)?, start_pos.as_deref().filter(|p| !p.is_empty()))?;
+    Ok(())
+}
+
+// Asserts that a time control is either a clock or a number of days per move (correspondence), returning
+// a `WebErr::BadReq` if it is neither.
+pub fn validate_time_control(time: Option<i32>, increment: Option<i32>, days: Option<i32>) -> Result<(), WebErr> {
+    match days {
+        Some(d) if time.is_some() || increment.is_some() =>
+            Err(WebErr::BadReq(format!("{} days per move cannot be combined with a clock", d))),
+        Some(d) if !(1..=MAX_CLOCK_DAYS).contains(&d) =>
+            Err(WebErr::BadReq(format!("days per move must be from 1 to {}", MAX_CLOCK_DAYS))),
+        _ => Ok(()),
+    }
+}
+
 pub async fn join_game(
     client: &web::Data<PrismaClient>,
     game: &game::Data,
@@ -166,6 +208,7 @@ pub async fn join_game(
     provisional: bool,
     broadcaster: &web::Data<Mutex<Broadcaster>>,
     player_stats: &web::Data<Mutex<PlayerStats>>,
+    hourglass: &web::Data<Mutex<Hourglass>>,
 ) -> Result<game::Data, WebErr> {
     let updated_game = client
         .game()
@@ -177,6 +220,7 @@ pub async fn join_game(
                     game::first_rating::set(Some(rating)),
                     game::first_prov::set(Some(provisional)),
                     game::status::set(GameStatus::Started.to_string()),
+                    game::move_deadline::set(game.get_new_move_deadline()),
                 ]
             } else {
                 vec![
@@ -184,6 +228,7 @@ pub async fn join_game(
                     game::second_rating::set(Some(rating)),
                     game::second_prov::set(Some(provisional)),
                     game::status::set(GameStatus::Started.to_string()),
+                    game::move_deadline::set(game.get_new_move_deadline()),
                 ]
             },
         )
@@ -204,6 +249,7 @@ pub async fn join_game(
         id: game.id.clone(),
     }));
     player_stats.lock().update_games(1, &broadcaster.lock());
+    hourglass.lock().start_turn(&updated_game)?;
 
     set_user_playing(&client, &updated_game.first_username.clone().unwrap(), Some([env::var("DOMAIN").unwrap(), "/game/".to_string(), game.id.clone()].concat())).await?;
     set_user_playing(&client, &updated_game.second_username.clone().unwrap(), Some([env::var("DOMAIN").unwrap(), "/game/".to_string(), game.id.clone()].concat())).await?;
@@ -3,7 +3,7 @@ use actix_web::web;
 use strum::IntoEnumIterator;
 
 use crate::common::WebErr;
-use crate::models::general::{GameKey, GamePerf, Profile};
+use crate::models::general::{GameKey, GamePerf, Profile, Speed};
 use crate::prisma::{user, PrismaClient, perf};
 use crate::models::req::CreateUserReq;
 use super::general::get_user_with_relations;
@@ -64,10 +64,11 @@ impl CreateUserReq {
         client
             .perf()
             .create_many(
-                GameKey::iter().map(|k|
+                GameKey::iter().flat_map(|k| Speed::iter().map(move |speed|
                     perf::create_unchecked(
                         self.username.clone(),
                         k.to_string(),
+                        speed.to_string(),
                         GamePerf::default().rating,
                         GamePerf::default().rd,
                         GamePerf::default().volatility,
@@ -76,7 +77,7 @@ impl CreateUserReq {
                         GamePerf::default().prov,
                         vec![],
                     )
-                ).collect()
+                )).collect()
             )
             .exec()
             .await
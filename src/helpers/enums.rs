@@ -1,35 +1,36 @@
+use std::str::FromStr;
+use strum::EnumMessage;
+
 use crate::common::WebErr;
-use crate::models::general::GameKey;
+use crate::models::general::{GameKey, Speed};
 use crate::models::events::Visibility;
 
 
 impl GameKey {
-    pub fn to_string(&self) -> String {
-        match self {
-            GameKey::TTT => "ttt",
-            GameKey::UTTT => "uttt",
-            GameKey::C4 => "c4",
-            GameKey::PC => "pc",
-        }.to_string()
+    pub fn get_game_name(string: &str) -> Result<String, WebErr> {
+        Ok(GameKey::from_str(string)?
+            .get_message()
+            .ok_or(WebErr::Internal(format!("game key {} is missing a display name", string)))?
+            .to_string())
     }
+}
 
-    pub fn from_str(string: &str) -> Result<Self, WebErr> {
-        match string {
-            "ttt" => Ok(GameKey::TTT),
-            "uttt" => Ok(GameKey::UTTT),
-            "c4" => Ok(GameKey::C4),
-            "pc" => Ok(GameKey::PC),
-            _ => Err(WebErr::NotFound(format!("provided game key string does not match an enum variant"))),
+impl Speed {
+    // gets the speed of a time control from its estimated length, the initial time plus 40 increments
+    pub fn from_clock(initial: Option<i32>, increment: Option<i32>, days: Option<i32>) -> Speed {
+        if days.is_some() {
+            return Speed::Correspondence;
         }
-    }
+        let initial = match initial {
+            Some(initial) => initial as i64,
+            None => return Speed::Untimed,
+        };
 
-    pub fn get_game_name(string: &str) -> Result<String, WebErr> {
-        match string {
-            "ttt" => Ok("Tic-Tac-Toe".to_string()),
-            "uttt" => Ok("Ultimate Tic-Tac-Toe".to_string()),
-            "c4" => Ok("Connect 4".to_string()),
-            "pc" => Ok("Pokémon Chess".to_string()),
-            _ => Err(WebErr::NotFound(format!("provided game key string does not match an enum variant"))),
+        match (initial + 40 * increment.unwrap_or(0) as i64) / 1000 {
+            t if t < 3 * 60 => Speed::Bullet,
+            t if t < 8 * 60 => Speed::Blitz,
+            t if t < 25 * 60 => Speed::Rapid,
+            _ => Speed::Classical,
         }
     }
 }
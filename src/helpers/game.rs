@@ -1,15 +1,17 @@
-use std::cmp::max;
+use std::cmp::{max, min};
 use std::str::FromStr;
 use actix_web::web;
 use glicko_2::game::compete;
-use glicko_2::Rating;
+use glicko_2::{Rating, Tuning};
 
 use crate::models::res::{CreateGameResponse, GameResponse, LobbyResponse};
-use crate::models::general::{TimeControl, Player, GameStatus, GameType, Offer, GameKey, EndType, Side, GamePerf, ProfileGame};
+use crate::models::general::{TimeControl, ClockType, RatingPreview, Speed, Player, GameStatus, GameType, Offer, GameKey, EndType, Side, GamePerf, ProfileGame};
 use crate::models::events::{GameState, GameFullEvent, GameEventType, Visibility, Chat};
-use crate::prisma::{game, PrismaClient, user, perf};
+use crate::prisma::{game, PrismaClient, user, perf, rating_history};
 use crate::common::WebErr;
-use super::general::time_millis;
+use super::general::{time_millis, DAY_MILLIS};
+use super::perf::PROVISIONAL_RD;
+use super::moves::rules::{GameBoard, create_board};
 
 
 impl game::Data {
@@ -34,10 +36,7 @@ impl game::Data {
                 key: self.game_key.clone(),
                 name: GameKey::get_game_name(&self.game_key)?,
             },
-            time_control: TimeControl {
-                initial: self.clock_initial,
-                increment: self.clock_increment,
-            },
+            time_control: self.to_time_control(),
             first_player: match game.first_user().unwrap() {
                 Some(u) => Some(Player {
                     username: u.username.clone(),
@@ -56,14 +55,16 @@ impl game::Data {
             },
             start_pos: self.start_pos.clone(),
             game_state: GameState {
-                ftime: self.get_new_first_time()?,
-                stime: self.get_new_second_time()?,
+                ftime: self.get_new_first_time(0)?,
+                stime: self.get_new_second_time(0)?,
                 moves: self.get_moves_vec(),
                 status: GameStatus::from_str(&self.status)?,
                 end_type: None,
                 draw_offer: Offer::None,
+                takeback_offer: Offer::None,
                 frating_diff: None,
                 srating_diff: None,
+                move_deadline: self.move_deadline,
             },
         })
     }
@@ -77,10 +78,7 @@ impl game::Data {
                 key: self.game_key.clone(),
                 name: GameKey::get_game_name(&self.game_key)?,
             },
-            time_control: TimeControl {
-                initial: self.clock_initial,
-                increment: self.clock_increment,
-            },
+            time_control: self.to_time_control(),
             created_at: self.created_at.to_string(),
             first: match self.first_user().unwrap() {
                 Some(u) => Some(Player {
@@ -109,10 +107,7 @@ impl game::Data {
                 key: self.game_key.clone(),
                 name: GameKey::get_game_name(&self.game_key)?,
             },
-            time_control: TimeControl {
-                initial: self.clock_initial,
-                increment: self.clock_increment,
-            },
+            time_control: self.to_time_control(),
             created_at: self.created_at.to_string(),
             first: Player {
                 username: self.first_user().unwrap().unwrap().username.clone(),
@@ -139,10 +134,7 @@ impl game::Data {
                 key: self.game_key.clone(),
                 name: GameKey::get_game_name(&self.game_key)?,
             },
-            time_control: TimeControl {
-                initial: self.clock_initial,
-                increment: self.clock_increment,
-            },
+            time_control: self.to_time_control(),
             created_at: self.created_at.to_string(),
             first: Player {
                 username: self.first_username.clone().unwrap(),
@@ -166,8 +158,8 @@ impl game::Data {
                 }
             })).flatten().collect(),
             state: GameState {
-                ftime: self.get_new_first_time()?,
-                stime: self.get_new_second_time()?,
+                ftime: self.get_new_first_time(0)?,
+                stime: self.get_new_second_time(0)?,
                 moves: if self.moves.len() > 0 {
                     self.moves.split(" ").map(|s| s.to_string()).collect()
                 } else {
@@ -179,8 +171,10 @@ impl game::Data {
                     None => None,
                 },
                 draw_offer: Offer::from_str(&self.draw_offer)?,
+                takeback_offer: Offer::from_str(&self.takeback_offer)?,
                 frating_diff: rating_diffs.0,
                 srating_diff: rating_diffs.1,
+                move_deadline: self.move_deadline,
             },
         })
     }
@@ -217,13 +211,25 @@ impl game::Data {
                 key: self.game_key.clone(),
                 name: GameKey::get_game_name(&self.game_key)?,
             },
-            time_control: TimeControl {
-                initial: self.clock_initial,
-                increment: self.clock_increment,
-            },
+            time_control: self.to_time_control(),
+            rating_preview: None,
         })
     }
 
+    pub fn to_time_control(&self) -> TimeControl {
+        TimeControl {
+            initial: self.clock_initial,
+            increment: self.clock_increment,
+            days: self.clock_days,
+            clock_type: ClockType::from_str(&self.clock_type).unwrap_or_default(),
+        }
+    }
+
+    // gets the rating pool this game is played in
+    pub fn get_speed(&self) -> Speed {
+        Speed::from_clock(self.clock_initial, self.clock_increment, self.clock_days)
+    }
+
     // Asserts that the provided user is in the game, and it has started.
     pub fn validate(&self, username: &str) -> Result<game::Data, WebErr> {
         if GameStatus::from_str(&self.status)? != GameStatus::Started ||
@@ -235,9 +241,11 @@ impl game::Data {
     }
 
     // Asserts that the provided user is in the game, and it has ended.
+    // aborted games are stored as draws, but never really started, so they can't be rematched
     pub fn validate_ended(&self, username: &str) -> Result<game::Data, WebErr> {
         let status = GameStatus::from_str(&self.status)?;
-        if status != GameStatus::FirstWon && status != GameStatus::SecondWon && status != GameStatus::Draw ||
+        let aborted = self.win_type.as_deref() == Some(EndType::Abort.to_string().as_str());
+        if status != GameStatus::FirstWon && status != GameStatus::SecondWon && status != GameStatus::Draw || aborted ||
             self.first_username.clone().unwrap() != username && self.second_username.clone().unwrap() != username {
             Err(WebErr::Forbidden(format!("could not validate, game not ended or not a player")))
         } else {
@@ -245,41 +253,97 @@ impl game::Data {
         }
     }
 
-    // helpers to get updated first and second times
-    pub fn get_new_first_time(&self) -> Result<Option<i32>, WebErr> {
-        if GameStatus::from_str(&self.status)? != GameStatus::Started {
-            return Ok(self.first_time);
+    // helpers to get updated first and second times, including the bonus for the move being made. `lag` is
+    // the compensation taken from the mover's lag quota, which is credited back when charging their move
+    pub fn get_new_first_time(&self, lag: i32) -> Result<Option<i32>, WebErr> {
+        self.get_new_time(self.first_time, true, Some(lag))
+    }
+
+    pub fn get_new_second_time(&self, lag: i32) -> Result<Option<i32>, WebErr> {
+        self.get_new_time(self.second_time, false, Some(lag))
+    }
+
+    // helpers to get the time players have left before their flag falls, without any bonus
+    pub fn get_first_time_left(&self) -> Result<Option<i32>, WebErr> {
+        self.get_new_time(self.first_time, true, None)
+    }
+
+    pub fn get_second_time_left(&self) -> Result<Option<i32>, WebErr> {
+        self.get_new_time(self.second_time, false, None)
+    }
+
+    // takes the time since the last move off the clock of the player to move, once both players have
+    // moved. Fischer increment is added, Bronstein delay gives back up to the increment of the time used,
+    // and simple delay only starts the clock once the increment has passed. moves being charged (with
+    // `lag` set) don't pay for the network time they were compensated for
+    fn get_new_time(&self, time: Option<i32>, first: bool, lag: Option<i32>) -> Result<Option<i32>, WebErr> {
+        let t = match time {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+        if GameStatus::from_str(&self.status)? != GameStatus::Started || self.num_moves() < 2 || self.first_to_move()? != first {
+            return Ok(Some(t));
         }
-        Ok(match self.first_time {
-            Some(t) => if self.num_moves() >= 2 && self.num_moves() % 2 == 0 {
-                Some(max(0, t - (time_millis() - self.last_move_time) as i32 + self.clock_increment.unwrap()))
-            } else {
-                Some(t)
-            },
-            None => None,
+
+        let with_bonus = lag.is_some();
+        let elapsed = (time_millis() - self.last_move_time) as i32;
+        let elapsed = elapsed - min(elapsed, lag.unwrap_or(0).max(0));
+        let increment = self.clock_increment.unwrap_or(0);
+        let spent = match ClockType::from_str(&self.clock_type)? {
+            ClockType::Fischer if with_bonus => elapsed - increment,
+            ClockType::Bronstein if with_bonus => elapsed - min(elapsed, increment),
+            ClockType::Delay => max(0, elapsed - increment),
+            _ => elapsed,
+        };
+        Ok(Some(max(0, t - spent)))
+    }
+
+    // helper to get how long until the flag of a player with `time` left falls, when their turn
+    // started `elapsed` millis ago. with simple delay, the clock only starts once the delay passes
+    pub fn get_millis_to_flag(&self, time: i32, elapsed: i32) -> Result<i32, WebErr> {
+        Ok(match ClockType::from_str(&self.clock_type)? {
+            ClockType::Delay => time + self.clock_increment.unwrap_or(0) - elapsed,
+            _ => time - elapsed,
         })
     }
 
-    pub fn get_new_second_time(&self) -> Result<Option<i32>, WebErr> {
-        if GameStatus::from_str(&self.status)? != GameStatus::Started {
-            return Ok(self.second_time);
+    // helper to get the deadline for the next move of a correspondence game, counted from now
+    pub fn get_new_move_deadline(&self) -> Option<i64> {
+        self.clock_days.map(|days| time_millis() + days as i64 * DAY_MILLIS)
+    }
+
+    // helper to create an empty board for this game, seeded with its custom start position if it has one
+    pub fn new_board(&self) -> Result<Box<dyn GameBoard>, WebErr> {
+        create_board(
+            &GameKey::from_str(&self.game_key)?,
+            self.start_pos.as_deref().filter(|p| !p.is_empty()),
+        )
+    }
+
+    // helper to replay this game's moves onto a new board
+    pub fn get_board(&self) -> Result<Box<dyn GameBoard>, WebErr> {
+        let mut board = self.new_board()?;
+        for m in self.get_moves_vec_str() {
+            let is_first = board.first_to_move();
+            board.process_move(m, is_first)?;
         }
-        match self.second_time {
-            Some(t) => if self.num_moves() >= 2 && self.num_moves() % 2 == 1 {
-                Ok(Some(max(0, t - (time_millis() - self.last_move_time) as i32 + self.clock_increment.unwrap())))
-            } else {
-                Ok(Some(t))
-            },
-            None => Ok(None),
+        Ok(board)
+    }
+
+    // helper to get whether the first player is to move. players don't always alternate (e.g. completing
+    // a box in dots and boxes earns another move), so this is saved with every move. games without it saved
+    // (no moves yet, or from before it was) ask the rules engine, replaying the moves so far
+    pub fn first_to_move(&self) -> Result<bool, WebErr> {
+        match self.first_to_move {
+            Some(first_to_move) => Ok(first_to_move),
+            None => Ok(self.get_board()?.first_to_move()),
         }
     }
 
-    // helper to get number of moves
+    // helper to get number of moves. every move is a single space-free token, even ones made of
+    // several steps like a checkers multi-jump ("b6-d4-f2"), so this is also the number of turns played
     pub fn num_moves(&self) -> usize {
-        if self.moves.len() == 0 {
-            return 0;
-        }
-        self.moves.split(" ").collect::<Vec<&str>>().len()
+        self.get_moves_vec_str().len()
     }
 
     // helper to convert moves string to vec
@@ -322,10 +386,24 @@ impl game::Data {
         }
     }
 
+    // claiming victory against a player who left wins the game for the claiming player, otherwise it is drawn
+    pub fn get_claim_game_status(&self, value: &bool, username: &str) -> GameStatus {
+        match (value, self.first_username.clone().unwrap() == username) {
+            (true, true) => GameStatus::FirstWon,
+            (true, false) => GameStatus::SecondWon,
+            (false, _) => GameStatus::Draw,
+        }
+    }
+
     pub fn get_timeout_game_status(&self, username: &str) -> Result<GameStatus, WebErr> {
-        Ok(if self.first_username.clone().unwrap() == username && self.get_new_first_time()?.unwrap() <= 0 {
+        // correspondence games have no clock, only a deadline for each move
+        if self.move_deadline.is_some_and(|d| time_millis() >= d) {
+            return Ok(self.get_resign_game_status(username));
+        }
+
+        Ok(if self.first_username.clone().unwrap() == username && self.get_first_time_left()?.is_some_and(|t| t <= 0) {
             GameStatus::SecondWon
-        } else if self.second_username.clone().unwrap() == username && self.get_new_second_time()?.unwrap() <= 0 {
+        } else if self.second_username.clone().unwrap() == username && self.get_second_time_left()?.is_some_and(|t| t <= 0) {
             GameStatus::FirstWon
         } else {
             GameStatus::from_str(&self.status)?
@@ -347,6 +425,40 @@ impl game::Data {
         })
     }
 
+    // takeback offers work like draw offers, with the offering player asking to take back their last move
+    pub fn get_new_takeback_offer(&self, value: &bool, username: &str) -> Result<Offer, WebErr> {
+        Ok(match (
+            self.first_username.clone().unwrap() == username,
+            value,
+            Offer::from_str(&self.takeback_offer)?,
+        ) {
+            (true, true, Offer::None) => Offer::First,
+            (false, true, Offer::None) => Offer::Second,
+            (true, true, Offer::Second) => Offer::Agreed,
+            (false, true, Offer::First) => Offer::Agreed,
+            (_, false, _) => Offer::None,
+            _ => Offer::from_str(&self.takeback_offer)?,
+        })
+    }
+
+    // helper to get the number of moves to take back so the given player is to move again before their
+    // last move. this is usually one or two moves, but can be more when a player moved several times
+    // in a row (e.g. completing boxes in dots and boxes)
+    pub fn get_takeback_moves(&self, first: bool) -> Result<Option<usize>, WebErr> {
+        let moves = self.get_moves_vec_str();
+        let mut board = self.new_board()?;
+        let mut last_move = None;
+
+        for (i, m) in moves.iter().enumerate() {
+            let is_first = board.first_to_move();
+            if is_first == first {
+                last_move = Some(i);
+            }
+            board.process_move(m, is_first)?;
+        }
+        Ok(last_move.map(|i| moves.len() - i))
+    }
+
     pub fn get_new_rematch_offer(&self, value: &bool, username: &str) -> Result<Offer, WebErr> {
         Ok(match (
             self.first_username.clone().unwrap() == username,
@@ -365,34 +477,52 @@ impl game::Data {
     
     pub fn get_rating_diffs(&self, new_status: GameStatus) -> Result<(Option<i32>, Option<i32>), WebErr> {
         let first_user = self.first_user().or(Err(WebErr::Internal(format!("first user not fetched"))))?;
-        let second_user = self.first_user().or(Err(WebErr::Internal(format!("second user not fetched"))))?;
+        let second_user = self.second_user().or(Err(WebErr::Internal(format!("second user not fetched"))))?;
 
         if !self.rated || first_user.is_none() || second_user.is_none() {
             return Ok((None, None));
         }
 
-        let first_tuning = first_user.unwrap().get_tuning(&self.game_key)?;
-        let second_tuning = second_user.unwrap().get_tuning(&self.game_key)?;
+        let first_tuning = first_user.unwrap().get_tuning(&self.game_key, self.get_speed())?;
+        let second_tuning = second_user.unwrap().get_tuning(&self.game_key, self.get_speed())?;
 
-        let mut first_rating = Rating::new(&first_tuning);
-        let mut second_rating = Rating::new(&second_tuning);
-        let first_old = first_rating.mu;
-        let second_old = second_rating.mu;
+        let (first_diff, second_diff) = compete_rating_diffs(&first_tuning, &second_tuning, new_status);
+        Ok((Some(first_diff), Some(second_diff)))
+    }
 
-        match new_status {
-            GameStatus::FirstWon => compete(&mut first_rating, &mut second_rating, false),
-            GameStatus::SecondWon => compete(&mut second_rating, &mut first_rating, false),
-            GameStatus::Draw => compete(&mut first_rating, &mut second_rating, true),
-            _ => {},
+    // helper to get how much a user's rating would change by winning, drawing or losing against the
+    // player waiting in a rated lobby or challenge
+    pub fn get_rating_preview(&self, user: &user::Data) -> Result<Option<RatingPreview>, WebErr> {
+        let first_user = self.first_user().or(Err(WebErr::Internal(format!("first user not fetched"))))?;
+        let second_user = self.second_user().or(Err(WebErr::Internal(format!("second user not fetched"))))?;
+
+        let opponent = match (first_user, second_user) {
+            (Some(u), None) | (None, Some(u)) => u,
+            _ => return Ok(None),
+        };
+        if !self.rated || opponent.username == user.username {
+            return Ok(None);
         }
-        Ok((Some((first_rating.mu - first_old) as i32), Some((second_rating.mu - second_old) as i32)))
+
+        let tuning = user.get_tuning(&self.game_key, self.get_speed())?;
+        let opponent_tuning = opponent.get_tuning(&self.game_key, self.get_speed())?;
+
+        Ok(Some(RatingPreview {
+            win: compete_rating_diffs(&tuning, &opponent_tuning, GameStatus::FirstWon).0,
+            draw: compete_rating_diffs(&tuning, &opponent_tuning, GameStatus::Draw).0,
+            loss: compete_rating_diffs(&tuning, &opponent_tuning, GameStatus::SecondWon).0,
+        }))
     }
 
     pub async fn update_ratings(&self, client: &web::Data<PrismaClient>, new_status: GameStatus) -> Result<(), WebErr> {
+        if !self.rated {
+            return Ok(());
+        }
+
         let first_user = self.first_user().or(Err(WebErr::Internal(format!("first user not fetched"))))?.unwrap();
         let second_user = self.second_user().or(Err(WebErr::Internal(format!("second user not fetched"))))?.unwrap();
-        let first_tuning = first_user.get_tuning(&self.game_key)?;
-        let second_tuning = second_user.get_tuning(&self.game_key)?;
+        let first_tuning = first_user.get_tuning(&self.game_key, self.get_speed())?;
+        let second_tuning = second_user.get_tuning(&self.game_key, self.get_speed())?;
         let mut first_rating = Rating::new(&first_tuning);
         let mut second_rating = Rating::new(&second_tuning);
         let first_old = first_rating.mu;
@@ -405,8 +535,8 @@ impl game::Data {
             _ => {},
         }
 
-        let mut first_prog = GamePerf::prog_from_str(&first_user.get_prog(&self.game_key)?)?;
-        let mut second_prog = GamePerf::prog_from_str(&second_user.get_prog(&self.game_key)?)?;
+        let mut first_prog = GamePerf::prog_from_str(&first_user.get_prog(&self.game_key, self.get_speed())?)?;
+        let mut second_prog = GamePerf::prog_from_str(&second_user.get_prog(&self.game_key, self.get_speed())?)?;
         first_prog.push(first_rating.mu - first_old);
         second_prog.push(second_rating.mu - second_old);
         first_prog.remove(0);
@@ -415,12 +545,13 @@ impl game::Data {
         client
             .perf()
             .update(
-                perf::username_game_key(self.first_username.clone().unwrap(), self.game_key.clone()),
+                perf::username_game_key_speed(self.first_username.clone().unwrap(), self.game_key.clone(), self.get_speed().to_string()),
                 vec![
                     perf::rating::set(first_rating.mu),
                     perf::rd::set(first_rating.phi),
                     perf::volatility::set(first_rating.sigma),
                     perf::prog::set(GamePerf::stringify_prog(first_prog)),
+                    perf::prov::set(first_rating.phi > PROVISIONAL_RD),
                 ],
             )
             .exec()
@@ -429,23 +560,57 @@ impl game::Data {
         client
             .perf()
             .update(
-                perf::username_game_key(self.second_username.clone().unwrap(), self.game_key.clone()),
+                perf::username_game_key_speed(self.second_username.clone().unwrap(), self.game_key.clone(), self.get_speed().to_string()),
                 vec![
                     perf::rating::set(second_rating.mu),
                     perf::rd::set(second_rating.phi),
                     perf::volatility::set(second_rating.sigma),
                     perf::prog::set(GamePerf::stringify_prog(second_prog)),
+                    perf::prov::set(second_rating.phi > PROVISIONAL_RD),
                 ],
             )
             .exec()
             .await
             .or(Err(WebErr::Internal(format!("error updating perfs for user {}", self.second_username.clone().unwrap()))))?;
+
+        for (username, rating) in [(self.first_username.clone().unwrap(), first_rating), (self.second_username.clone().unwrap(), second_rating)] {
+            client
+                .rating_history()
+                .create(
+                    user::username::equals(username.clone()),
+                    self.game_key.clone(),
+                    self.get_speed().to_string(),
+                    game::id::equals(self.id.clone()),
+                    rating.mu,
+                    vec![rating_history::rd::set(Some(rating.phi))],
+                )
+                .exec()
+                .await
+                .or(Err(WebErr::Internal(format!("error adding rating history for user {}", username))))?;
+        }
         Ok(())
     }
 }
 
+// glicko-2 rating changes for the first and second player of a game ending with the given status
+fn compete_rating_diffs(first_tuning: &Tuning, second_tuning: &Tuning, new_status: GameStatus) -> (i32, i32) {
+    let mut first_rating = Rating::new(first_tuning);
+    let mut second_rating = Rating::new(second_tuning);
+    let first_old = first_rating.mu;
+    let second_old = second_rating.mu;
+
+    match new_status {
+        GameStatus::FirstWon => compete(&mut first_rating, &mut second_rating, false),
+        GameStatus::SecondWon => compete(&mut second_rating, &mut first_rating, false),
+        GameStatus::Draw => compete(&mut first_rating, &mut second_rating, true),
+        _ => {},
+    }
+    ((first_rating.mu - first_old) as i32, (second_rating.mu - second_old) as i32)
+}
+
 pub trait LobbyVec {
     fn to_lobby_vec(&self) -> Result<Vec<LobbyResponse>, WebErr>;
+    fn to_lobby_vec_with_preview(&self, user: &user::Data) -> Result<Vec<LobbyResponse>, WebErr>;
 }
 
 impl LobbyVec for Vec<game::Data> {
@@ -455,4 +620,14 @@ impl LobbyVec for Vec<game::Data> {
             |g| Ok::<LobbyResponse, WebErr>(g.to_lobby_res(g.random_side)?)
         ).flatten().collect())
     }
+
+    // convert vec of games to vec of LobbyResponse structs, with what is at stake for the given user
+    fn to_lobby_vec_with_preview(&self, user: &user::Data) -> Result<Vec<LobbyResponse>, WebErr> {
+        Ok(self.iter().map(
+            |g| Ok::<LobbyResponse, WebErr>(LobbyResponse {
+                rating_preview: g.get_rating_preview(user)?,
+                ..g.to_lobby_res(g.random_side)?
+            })
+        ).flatten().collect())
+    }
 }
@@ -7,7 +7,8 @@ use nanoid::nanoid;
 
 use crate::common::WebErr;
 use crate::models::events::{LobbyEvent, AllLobbiesEvent, LobbyEventType, Visibility, ChatAlertEvent, GameEventType, GameEvent, GameStateEvent};
-use crate::models::general::{EndType, Offer, Conversation, Challenge};
+use crate::models::general::{EndType, Offer, Conversation, Challenge, GameStatus};
+use crate::lumber_mill::LumberMill;
 use crate::player_stats::PlayerStats;
 use crate::prisma::{user, PrismaClient, message, game, conversation, challenge};
 use crate::sse::Broadcaster;
@@ -179,18 +180,28 @@ pub async fn timeout_player(
     username: String,
 ) -> Result<(), WebErr> {
     let game = get_game_with_relations(&client, &game_id).await?.validate(&username)?;
-    let rating_diffs = game.get_rating_diffs(game.get_timeout_game_status(&username)?)?;
+
+    // a glass can go off just as a move lands or more time is given, with the player's time not yet up.
+    // the glass armed for their new turn takes over then
+    let status = game.get_timeout_game_status(&username)?;
+    if status != GameStatus::FirstWon && status != GameStatus::SecondWon {
+        return Ok(());
+    }
+    let rating_diffs = game.get_rating_diffs(status)?;
 
     broadcaster.lock().game_send(&game_id, GameEvent::GameStateEvent(GameStateEvent {
         r#type: GameEventType::GameState,
-        ftime: game.get_new_first_time()?,
-        stime: game.get_new_second_time()?,
+        ftime: game.get_new_first_time(0)?,
+        stime: game.get_new_second_time(0)?,
         moves: vec![],
-        status: game.get_timeout_game_status(&username)?,
+        status,
         end_type: Some(EndType::Timeout),
         draw_offer: Offer::None,
+        takeback_offer: Offer::None,
+        undo: 0,
         frating_diff: rating_diffs.0,
         srating_diff: rating_diffs.1,
+        timestamp: time_millis(),
     }));
 
     let chat_alert_event = ChatAlertEvent {
@@ -202,7 +213,7 @@ pub async fn timeout_player(
 
     set_user_playing(&client, &game.first_username.clone().unwrap(), None).await?;
     set_user_playing(&client, &game.second_username.clone().unwrap(), None).await?;
-    game.update_ratings(&client, game.get_timeout_game_status(&username)?).await?;
+    game.update_ratings(&client, status).await?;
 
     player_stats.lock().update_games(-1, &broadcaster.lock());
 
@@ -211,8 +222,9 @@ pub async fn timeout_player(
         .update(
             game::id::equals(game_id.clone()),
             vec![
-                game::status::set(game.get_timeout_game_status(&username)?.to_string()),
+                game::status::set(status.to_string()),
                 game::win_type::set(Some(EndType::Timeout.to_string())),
+                game::end_time::set(Some(time_millis())),
                 game::draw_offer::set(Offer::None.to_string()),
             ],
         )
@@ -223,6 +235,81 @@ pub async fn timeout_player(
     Ok(())
 }
 
+// ends a game where the given player never made their first move. nobody wins, so ratings are left
+// alone, and both players are free to start new games
+pub async fn abort_game(
+    client: &web::Data<PrismaClient>,
+    broadcaster: &web::Data<Mutex<Broadcaster>>,
+    mill: &web::Data<Mutex<LumberMill>>,
+    player_stats: &web::Data<Mutex<PlayerStats>>,
+    game_id: String,
+    username: String,
+) -> Result<(), WebErr> {
+    let game = get_game_by_id(&client, &game_id).await?.validate(&username)?;
+
+    // the glass can go off just as the player's first move lands, and then the game carries on
+    let first = game.first_username.clone().unwrap() == username;
+    if game.num_moves() >= 2 || game.first_to_move()? != first {
+        return Ok(());
+    }
+
+    // the game is only aborted if no move was saved since it was read above
+    let aborted = client
+        .game()
+        .update_many(
+            vec![
+                game::id::equals(game_id.clone()),
+                game::status::equals(GameStatus::Started.to_string()),
+                game::moves::equals(game.moves.clone()),
+            ],
+            vec![
+                game::status::set(GameStatus::Draw.to_string()),
+                game::win_type::set(Some(EndType::Abort.to_string())),
+                game::end_time::set(Some(time_millis())),
+                game::draw_offer::set(Offer::None.to_string()),
+            ],
+        )
+        .exec()
+        .await
+        .or(Err(WebErr::Internal(format!("error updating game with id {} to abort", game_id))))?;
+    if aborted == 0 {
+        return Ok(());
+    }
+
+    broadcaster.lock().game_send(&game_id, GameEvent::GameStateEvent(GameStateEvent {
+        r#type: GameEventType::GameState,
+        ftime: game.first_time,
+        stime: game.second_time,
+        moves: vec![],
+        status: GameStatus::Draw,
+        end_type: Some(EndType::Abort),
+        draw_offer: Offer::None,
+        takeback_offer: Offer::None,
+        undo: 0,
+        frating_diff: None,
+        srating_diff: None,
+        timestamp: time_millis(),
+    }));
+
+    let chat_alert_event = ChatAlertEvent {
+        r#type: GameEventType::ChatAlert,
+        message: format!("{} did not make a first move, game aborted", username),
+    };
+    add_chat_alert_event(&client, &game_id, &chat_alert_event).await?;
+    broadcaster.lock().game_send(&game_id, GameEvent::ChatAlertEvent(chat_alert_event));
+
+    set_user_playing(&client, &game.first_username.clone().unwrap(), None).await?;
+    set_user_playing(&client, &game.second_username.clone().unwrap(), None).await?;
+    set_user_can_start_game(&client, &game.first_username.clone().unwrap(), true).await?;
+    set_user_can_start_game(&client, &game.second_username.clone().unwrap(), true).await?;
+
+    player_stats.lock().update_games(-1, &broadcaster.lock());
+    mill.lock().boards.remove(&game_id);
+    mill.lock().clear_premove(&game_id);
+
+    Ok(())
+}
+
 pub async fn gen_nanoid(client: &web::Data<PrismaClient>) -> String {
     let alphabet: [char; 62] = [
         '1', '2', '3', '4', '5', '6', '7', '8', '9', '0',
@@ -246,6 +333,8 @@ pub async fn gen_nanoid(client: &web::Data<PrismaClient>) -> String {
     id
 }
 
+pub const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
 pub fn time_millis() -> i64 {
     SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
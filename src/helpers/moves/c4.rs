@@ -1,28 +1,103 @@
 use log::debug;
-use crate::models::general::MoveOutcome;
-use super::ttt::{PlayerSymbol, col_to_index, check_board_status};
+use crate::common::WebErr;
+use crate::models::general::{GameKey, MoveOutcome};
+use super::rules::GameRules;
+use super::ttt::{
+    PlayerSymbol, col_to_index, check_board_status, get_board_status, symbols_to_string, symbols_from_string,
+    position_fields, side_from_str, side_to_str,
+};
 
 
-// Validates a c4 move. A move is invalid if:
-// 1. It is not in the correct format ("a")
-// TODO: check if column is full
-pub fn validate_c4_move(new_move: &str) -> bool {
-    new_move.chars().nth(0).is_some_and(|c| matches!(c, 'a'..='g'))
+#[derive(Clone)]
+pub struct C4Board {
+    board: Vec<PlayerSymbol>,
+    first_to_move: bool,
 }
 
-pub fn process_c4_move(new_move: &str, board: &mut Vec<PlayerSymbol>, is_first: bool) -> MoveOutcome {
-    let col = col_to_index(new_move.chars().nth(0).unwrap());
-    let index = get_next_unfilled_index(board, col, 7).unwrap();
+impl Default for C4Board {
+    fn default() -> Self {
+        C4Board {
+            board: vec![PlayerSymbol::Empty; 42],
+            first_to_move: true,
+        }
+    }
+}
+
+impl GameRules for C4Board {
+    // Column index
+    type Move = usize;
+
+    const KEY: GameKey = GameKey::C4;
+
+    // Parses a c4 position in the format ".xo..../......./......./......./......./....... x": rows
+    // from the bottom up separated by '/', followed by the side to move.
+    fn from_position(position: &str) -> Result<Self, WebErr> {
+        let fields = position_fields(position, 2, ".xo..../......./......./......./......./....... x")?;
+        let board = C4Board {
+            board: symbols_from_string(fields[0], 6, 7)?,
+            first_to_move: side_from_str(fields[1])?,
+        };
+
+        if let Some(i) = (7..42).find(|i| board.board[*i] != PlayerSymbol::Empty && board.board[i - 7] == PlayerSymbol::Empty) {
+            return Err(WebErr::BadReq(format!(
+                "piece in column {} has an empty square below it",
+                (b'a' + (i % 7) as u8) as char,
+            )));
+        }
+        if get_board_status(&board.board, 6, 7, 4) != MoveOutcome::None {
+            return Err(WebErr::BadReq(format!("position {} has already been decided", position)));
+        }
+        Ok(board)
+    }
+
+    // Parses a c4 move in the format "a" into a column index.
+    fn parse_move(&self, new_move: &str) -> Result<usize, WebErr> {
+        let mut chars = new_move.chars();
+        match (chars.next(), chars.next()) {
+            (Some(col @ 'a'..='g'), None) => Ok(col_to_index(col)),
+            _ => Err(WebErr::BadReq(format!("move {} is not a column from \"a\" to \"g\"", new_move))),
+        }
+    }
+
+    // A c4 move is invalid if its column is already full.
+    fn check_move(&self, m: &usize) -> Result<(), WebErr> {
+        if get_next_unfilled_index(&self.board, *m, 7).is_none() {
+            return Err(WebErr::BadReq(format!("column {} is full", (b'a' + *m as u8) as char)));
+        }
+        Ok(())
+    }
+
+    fn apply_move(&mut self, m: usize, is_first: bool) -> MoveOutcome {
+        // Full columns are rejected by `check_move`
+        let index = get_next_unfilled_index(&self.board, m, 7).unwrap();
+
+        self.board[index] = if is_first {
+            PlayerSymbol::First
+        } else {
+            PlayerSymbol::Second
+        };
 
-    board[index] = if is_first {
-        PlayerSymbol::First
-    } else {
-        PlayerSymbol::Second
-    };
+        // TODO: better move num calc?
+        let move_num = self.board.iter().filter(|m| **m != PlayerSymbol::Empty).count();
+        self.first_to_move = !is_first;
+        check_board_status(index, move_num, &self.board, 6, 7, 4)
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.first_to_move
+    }
 
-    // TODO: better move num calc?
-    let move_num = board.iter().filter(|m| **m != PlayerSymbol::Empty).count();
-    check_board_status(index, move_num, board, 6, 7, 4)
+    fn move_list(&self) -> Vec<String> {
+        (0..7)
+            .filter(|col| get_next_unfilled_index(&self.board, *col, 7).is_some())
+            .map(|col| ((b'a' + col as u8) as char).to_string())
+            .collect()
+    }
+
+    // Serializes the board from the bottom row up, followed by the side to move.
+    fn serialize(&self) -> String {
+        format!("{} {}", symbols_to_string(&self.board, 7), side_to_str(self.first_to_move))
+    }
 }
 
 fn get_next_unfilled_index(board: &Vec<PlayerSymbol>, column: usize, columns: usize) -> Option<usize> {
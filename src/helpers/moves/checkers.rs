@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use super::rules::GameRules;
+use super::ttt::{col_to_index, row_to_index, position_fields, side_from_str, side_to_str};
+use crate::common::WebErr;
+use crate::models::general::{GameKey, MoveOutcome};
+
+
+// Number of plies (40 moves by each player) without a capture or a man moving before the game
+// is drawn
+const NO_PROGRESS_LIMIT: usize = 80;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Piece {
+    pub first: bool,
+    pub king: bool,
+}
+
+// English draughts. Squares are indexed from a1 (0) to h8 (63), rank by rank, and only the dark
+// squares (a1, c1, ..., h8) are played on. The first player starts on ranks 1 to 3 and moves
+// first.
+//
+// A move is the squares a piece visits joined by '-', e.g. "c3-d4" for a step or "b6-d4-f2" for a
+// double jump, so every move is still a single token in the game's moves string.
+#[derive(Clone)]
+pub struct CheckersBoard {
+    squares: Vec<Option<Piece>>,
+    first_to_move: bool,
+    // Plies since the last capture or man move
+    no_progress: usize,
+    // Occurrences of each position since the last capture or man move, for threefold repetition
+    history: HashMap<String, usize>,
+}
+
+impl Default for CheckersBoard {
+    fn default() -> Self {
+        let mut squares = vec![None; 64];
+        for i in (0..64).filter(|i| is_dark(*i)) {
+            if i / 8 < 3 {
+                squares[i] = Some(Piece { first: true, king: false });
+            } else if i / 8 > 4 {
+                squares[i] = Some(Piece { first: false, king: false });
+            }
+        }
+
+        CheckersBoard::new(squares, true, 0)
+    }
+}
+
+impl GameRules for CheckersBoard {
+    // Squares visited by the moving piece
+    type Move = Vec<usize>;
+
+    const KEY: GameKey = GameKey::Checkers;
+
+    // Parses a checkers position in the format "x.x.x.x./.x.x.x.x/..../o.o.o.o. x 0": 8 rows from 1
+    // to 8 separated by '/', with 'x' and 'o' for men and 'X' and 'O' for kings, followed by the
+    // side to move and the number of plies without a capture or man move.
+    fn from_position(position: &str) -> Result<Self, WebErr> {
+        let fields = position_fields(position, 3, "x.x.x.x./.x.x.x.x/..../o.o.o.o. x 0")?;
+
+        let rows = fields[0].split('/').collect::<Vec<&str>>();
+        if rows.len() != 8 || rows.iter().any(|r| r.chars().count() != 8) {
+            return Err(WebErr::BadReq(format!("board {} is not 8 rows of 8 squares", fields[0])));
+        }
+
+        let mut squares = vec![None; 64];
+        for (i, c) in rows.iter().flat_map(|r| r.chars()).enumerate() {
+            squares[i] = match c {
+                '.' => None,
+                'x' | 'o' | 'X' | 'O' => Some(Piece {
+                    first: c.to_ascii_lowercase() == 'x',
+                    king: c.is_ascii_uppercase(),
+                }),
+                _ => return Err(WebErr::BadReq(format!("square {} is not one of 'x', 'o', 'X', 'O' or '.'", c))),
+            };
+            if squares[i].is_some() && !is_dark(i) {
+                return Err(WebErr::BadReq(format!("piece on light square {}", square_to_string(i))));
+            }
+        }
+
+        let no_progress = fields[2].parse::<usize>()
+            .ok()
+            .filter(|n| *n < NO_PROGRESS_LIMIT)
+            .ok_or(WebErr::BadReq(format!("ply count must be a number below {}", NO_PROGRESS_LIMIT)))?;
+
+        let board = CheckersBoard::new(squares, side_from_str(fields[1])?, no_progress);
+        if board.legal_moves(board.first_to_move).is_empty() {
+            return Err(WebErr::BadReq(format!("position {} has already been decided", position)));
+        }
+        Ok(board)
+    }
+
+    fn parse_move(&self, new_move: &str) -> Result<Vec<usize>, WebErr> {
+        let malformed = || WebErr::BadReq(format!("move {} is not in the format \"c3-d4\" or \"b6-d4-f2\"", new_move));
+
+        let path = new_move.split('-').map(|square| {
+            let mut chars = square.chars();
+            match (chars.next(), chars.next(), chars.next()) {
+                (Some(col @ 'a'..='h'), Some(row @ '1'..='8'), None) => Ok(col_to_index(col) + row_to_index(row) * 8),
+                _ => Err(malformed()),
+            }
+        }).collect::<Result<Vec<usize>, WebErr>>()?;
+
+        if path.len() < 2 {
+            return Err(malformed());
+        }
+        Ok(path)
+    }
+
+    // A checkers move is invalid if it is not one of the legal moves of the side to move. Captures
+    // are forced, and a capturing piece must keep jumping until it can't.
+    fn check_move(&self, m: &Vec<usize>) -> Result<(), WebErr> {
+        if !self.squares[m[0]].is_some_and(|p| p.first == self.first_to_move) {
+            return Err(WebErr::BadReq(format!("no piece of the side to move on {}", square_to_string(m[0]))));
+        }
+
+        let legal_moves = self.legal_moves(self.first_to_move);
+        if legal_moves.contains(m) {
+            return Ok(());
+        }
+        if legal_moves.iter().any(|l| is_jump(l)) && !is_jump(m) {
+            return Err(WebErr::BadReq(format!("a capture is available and must be played")));
+        }
+        Err(WebErr::BadReq(format!("move {} is not legal", path_to_string(m))))
+    }
+
+    fn apply_move(&mut self, m: Vec<usize>, is_first: bool) -> MoveOutcome {
+        let from = m[0];
+        let to = m[m.len() - 1];
+        let piece = self.squares[from].take().unwrap();
+
+        for step in m.windows(2) {
+            if step[0].abs_diff(step[1]) > 9 {
+                self.squares[(step[0] + step[1]) / 2] = None;
+            }
+        }
+
+        let crowned = !piece.king && to / 8 == if piece.first { 7 } else { 0 };
+        self.squares[to] = Some(Piece { first: piece.first, king: piece.king || crowned });
+        self.first_to_move = !is_first;
+
+        // Positions from before a capture or man move can never repeat
+        if is_jump(&m) || !piece.king {
+            self.no_progress = 0;
+            self.history.clear();
+        } else {
+            self.no_progress += 1;
+        }
+        let placement = self.placement();
+        *self.history.entry(placement.clone()).or_insert(0) += 1;
+
+        if self.legal_moves(!is_first).is_empty() {
+            return if is_first {
+                MoveOutcome::FirstWin
+            } else {
+                MoveOutcome::SecondWin
+            };
+        }
+        if self.history[&placement] >= 3 || self.no_progress >= NO_PROGRESS_LIMIT {
+            return MoveOutcome::Draw;
+        }
+        MoveOutcome::None
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.first_to_move
+    }
+
+    fn move_list(&self) -> Vec<String> {
+        self.legal_moves(self.first_to_move).iter().map(|m| path_to_string(m)).collect()
+    }
+
+    fn serialize(&self) -> String {
+        format!("{} {}", self.placement(), self.no_progress)
+    }
+}
+
+impl CheckersBoard {
+    fn new(squares: Vec<Option<Piece>>, first_to_move: bool, no_progress: usize) -> Self {
+        let mut board = CheckersBoard {
+            squares,
+            first_to_move,
+            no_progress,
+            history: HashMap::new(),
+        };
+        board.history.insert(board.placement(), 1);
+        board
+    }
+
+    // Gets all moves the given side can make. If any capture is available, only captures are legal.
+    pub fn legal_moves(&self, first: bool) -> Vec<Vec<usize>> {
+        let pieces = (0..64)
+            .filter(|i| self.squares[*i].is_some_and(|p| p.first == first))
+            .collect::<Vec<usize>>();
+
+        let mut jumps = vec![];
+        for from in pieces.iter() {
+            self.jump_moves(vec![*from], self.squares[*from].unwrap(), &mut jumps);
+        }
+        if !jumps.is_empty() {
+            return jumps;
+        }
+
+        let mut steps = vec![];
+        for from in pieces {
+            let piece = self.squares[from].unwrap();
+            for (dc, dr) in directions(piece) {
+                if let Some(to) = offset(from, dc, dr).filter(|to| self.squares[*to].is_none()) {
+                    steps.push(vec![from, to]);
+                }
+            }
+        }
+        steps
+    }
+
+    // Extends a capture sequence with every possible next jump, collecting the sequences that
+    // can't be extended any further. A man that is crowned ends its move.
+    fn jump_moves(&self, path: Vec<usize>, piece: Piece, moves: &mut Vec<Vec<usize>>) {
+        let current = path[path.len() - 1];
+        let crowned = path.len() > 1 && !piece.king && current / 8 == if piece.first { 7 } else { 0 };
+
+        let mut extended = false;
+        if !crowned {
+            for (dc, dr) in directions(piece) {
+                let (over, to) = match (offset(current, dc, dr), offset(current, 2 * dc, 2 * dr)) {
+                    (Some(over), Some(to)) => (over, to),
+                    _ => continue,
+                };
+
+                // The moving piece has left its starting square, and each piece can only be
+                // jumped once
+                let captured = path.windows(2).any(|step| (step[0] + step[1]) / 2 == over);
+                let landing_empty = self.squares[to].is_none() || to == path[0];
+                if !captured && landing_empty && self.squares[over].is_some_and(|p| p.first != piece.first) {
+                    let mut next = path.clone();
+                    next.push(to);
+                    self.jump_moves(next, piece, moves);
+                    extended = true;
+                }
+            }
+        }
+
+        if !extended && path.len() > 1 {
+            moves.push(path);
+        }
+    }
+
+    // Serializes the pieces and side to move.
+    fn placement(&self) -> String {
+        let rows = self.squares.chunks(8).map(|row| row.iter().map(|s| match s {
+            Some(Piece { first: true, king: false }) => 'x',
+            Some(Piece { first: false, king: false }) => 'o',
+            Some(Piece { first: true, king: true }) => 'X',
+            Some(Piece { first: false, king: true }) => 'O',
+            None => '.',
+        }).collect::<String>()).collect::<Vec<String>>().join("/");
+
+        format!("{} {}", rows, side_to_str(self.first_to_move))
+    }
+}
+
+// Men move diagonally forward, while kings can also move backward.
+fn directions(piece: Piece) -> Vec<(i32, i32)> {
+    let forward = if piece.first { 1 } else { -1 };
+    if piece.king {
+        vec![(1, forward), (-1, forward), (1, -forward), (-1, -forward)]
+    } else {
+        vec![(1, forward), (-1, forward)]
+    }
+}
+
+fn is_jump(path: &[usize]) -> bool {
+    path[0].abs_diff(path[1]) > 9
+}
+
+fn is_dark(square: usize) -> bool {
+    (square % 8 + square / 8) % 2 == 0
+}
+
+fn offset(square: usize, dc: i32, dr: i32) -> Option<usize> {
+    let col = (square % 8) as i32 + dc;
+    let row = (square / 8) as i32 + dr;
+
+    if (0..8).contains(&col) && (0..8).contains(&row) {
+        Some((col + row * 8) as usize)
+    } else {
+        None
+    }
+}
+
+fn square_to_string(square: usize) -> String {
+    format!("{}{}", (b'a' + (square % 8) as u8) as char, square / 8 + 1)
+}
+
+pub fn path_to_string(path: &[usize]) -> String {
+    path.iter().map(|s| square_to_string(*s)).collect::<Vec<String>>().join("-")
+}
@@ -0,0 +1,247 @@
+use std::cmp::Ordering;
+
+use super::rules::GameRules;
+use super::ttt::{PlayerSymbol, position_fields, side_from_str, side_to_str, symbols_from_string, symbols_to_string};
+use crate::common::WebErr;
+use crate::models::general::{GameKey, MoveOutcome};
+
+
+// Largest number of boxes along either side of the board
+const MAX_SIZE: usize = 10;
+
+// A line between two neighbouring dots, as (row, column) of its bottom or left dot.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Line {
+    Horizontal(usize, usize),
+    Vertical(usize, usize),
+}
+
+// Dots and Boxes. Players take turns drawing a line between two neighbouring dots, and a player
+// who completes the fourth side of a box claims it and moves again. Once every line is drawn, the
+// player with the most boxes wins.
+//
+// Dots are named like squares, from "a1" in the bottom left corner, and a move is the two dots a
+// line connects, e.g. "a1-b1".
+#[derive(Clone)]
+pub struct DABBoard {
+    rows: usize,
+    columns: usize,
+    // (rows + 1) rows of `columns` lines
+    horizontal: Vec<bool>,
+    // `rows` rows of (columns + 1) lines
+    vertical: Vec<bool>,
+    // `rows` rows of `columns` boxes, with the owner of each completed box
+    boxes: Vec<PlayerSymbol>,
+    first_to_move: bool,
+}
+
+impl Default for DABBoard {
+    fn default() -> Self {
+        DABBoard::new(5, 5)
+    }
+}
+
+impl GameRules for DABBoard {
+    type Move = Line;
+
+    const KEY: GameKey = GameKey::DotsAndBoxes;
+
+    // Parses a dots and boxes position in the format "-./../.. |../.|. ../.. x": the drawn
+    // ('-') horizontal lines of each row of dots, the drawn ('|') vertical lines of each row of
+    // boxes, the owner of each box, and the side to move. Rows go from the bottom up and are
+    // separated by '/'.
+    fn from_position(position: &str) -> Result<Self, WebErr> {
+        let fields = position_fields(position, 4, "-./../.. |../.|. ../.. x")?;
+
+        let rows = fields[2].split('/').count();
+        let columns = fields[2].split('/').next().unwrap().chars().count();
+        if !(1..=MAX_SIZE).contains(&rows) || !(1..=MAX_SIZE).contains(&columns) {
+            return Err(WebErr::BadReq(format!("board must have between 1 and {} rows and columns of boxes", MAX_SIZE)));
+        }
+
+        let mut board = DABBoard::new(rows, columns);
+        board.horizontal = lines_from_string(fields[0], '-', rows + 1, columns)?;
+        board.vertical = lines_from_string(fields[1], '|', rows, columns + 1)?;
+        board.boxes = symbols_from_string(fields[2], rows, columns)?;
+        board.first_to_move = side_from_str(fields[3])?;
+
+        for i in 0..board.boxes.len() {
+            if board.is_complete(i / columns, i % columns) != (board.boxes[i] != PlayerSymbol::Empty) {
+                return Err(WebErr::BadReq(format!(
+                    "box with bottom left dot {}{} must have an owner exactly when all 4 sides are drawn",
+                    (b'a' + (i % columns) as u8) as char,
+                    i / columns + 1,
+                )));
+            }
+        }
+        if board.boxes.iter().all(|b| *b != PlayerSymbol::Empty) {
+            return Err(WebErr::BadReq(format!("position {} has already been decided", position)));
+        }
+        Ok(board)
+    }
+
+    fn parse_move(&self, new_move: &str) -> Result<Line, WebErr> {
+        let malformed = || WebErr::BadReq(format!(
+            "move {} is not two neighbouring dots from \"a1\" to \"{}{}\", like \"a1-b1\"",
+            new_move,
+            (b'a' + self.columns as u8) as char,
+            self.rows + 1,
+        ));
+
+        let dots = new_move.split('-').map(|dot| {
+            let mut chars = dot.chars();
+            let col = match chars.next() {
+                Some(c @ 'a'..='z') => c as usize - 'a' as usize,
+                _ => return Err(malformed()),
+            };
+            let row_str = chars.as_str();
+            match row_str.parse::<usize>() {
+                Ok(row) if row >= 1 && row <= self.rows + 1 && col <= self.columns && row.to_string() == row_str =>
+                    Ok((row - 1, col)),
+                _ => Err(malformed()),
+            }
+        }).collect::<Result<Vec<(usize, usize)>, WebErr>>()?;
+
+        match dots[..] {
+            [(r1, c1), (r2, c2)] if r1 == r2 && c1.abs_diff(c2) == 1 => Ok(Line::Horizontal(r1, c1.min(c2))),
+            [(r1, c1), (r2, c2)] if c1 == c2 && r1.abs_diff(r2) == 1 => Ok(Line::Vertical(r1.min(r2), c1)),
+            _ => Err(malformed()),
+        }
+    }
+
+    // A dots and boxes move is invalid if its line has already been drawn.
+    fn check_move(&self, m: &Line) -> Result<(), WebErr> {
+        if self.is_drawn(*m) {
+            return Err(WebErr::BadReq(format!("line {} has already been drawn", self.line_to_string(*m))));
+        }
+        Ok(())
+    }
+
+    fn apply_move(&mut self, m: Line, is_first: bool) -> MoveOutcome {
+        let symbol = if is_first {
+            PlayerSymbol::First
+        } else {
+            PlayerSymbol::Second
+        };
+
+        // Each line borders at most two boxes
+        let bordering = match m {
+            Line::Horizontal(row, col) => {
+                self.horizontal[col + row * self.columns] = true;
+                [row.checked_sub(1).map(|r| (r, col)), Some((row, col)).filter(|_| row < self.rows)]
+            },
+            Line::Vertical(row, col) => {
+                self.vertical[col + row * (self.columns + 1)] = true;
+                [col.checked_sub(1).map(|c| (row, c)), Some((row, col)).filter(|_| col < self.columns)]
+            },
+        };
+
+        let mut completed = false;
+        for (row, col) in bordering.into_iter().flatten() {
+            if self.is_complete(row, col) {
+                self.boxes[col + row * self.columns] = symbol;
+                completed = true;
+            }
+        }
+
+        // Completing a box earns another move
+        self.first_to_move = if completed { is_first } else { !is_first };
+
+        if self.boxes.iter().any(|b| *b == PlayerSymbol::Empty) {
+            return MoveOutcome::None;
+        }
+
+        let first_boxes = self.boxes.iter().filter(|b| **b == PlayerSymbol::First).count();
+        let second_boxes = self.boxes.len() - first_boxes;
+        match first_boxes.cmp(&second_boxes) {
+            Ordering::Greater => MoveOutcome::FirstWin,
+            Ordering::Less => MoveOutcome::SecondWin,
+            Ordering::Equal => MoveOutcome::Draw,
+        }
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.first_to_move
+    }
+
+    fn move_list(&self) -> Vec<String> {
+        self.legal_moves().into_iter().map(|l| self.line_to_string(l)).collect()
+    }
+
+    fn serialize(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            lines_to_string(&self.horizontal, '-', self.columns),
+            lines_to_string(&self.vertical, '|', self.columns + 1),
+            symbols_to_string(&self.boxes, self.columns),
+            side_to_str(self.first_to_move),
+        )
+    }
+}
+
+impl DABBoard {
+    pub fn new(rows: usize, columns: usize) -> Self {
+        DABBoard {
+            rows,
+            columns,
+            horizontal: vec![false; (rows + 1) * columns],
+            vertical: vec![false; rows * (columns + 1)],
+            boxes: vec![PlayerSymbol::Empty; rows * columns],
+            first_to_move: true,
+        }
+    }
+
+    // Gets every line that has not been drawn yet.
+    pub fn legal_moves(&self) -> Vec<Line> {
+        let horizontal = (0..self.horizontal.len()).map(|i| Line::Horizontal(i / self.columns, i % self.columns));
+        let vertical = (0..self.vertical.len()).map(|i| Line::Vertical(i / (self.columns + 1), i % (self.columns + 1)));
+        horizontal.chain(vertical).filter(|l| !self.is_drawn(*l)).collect()
+    }
+
+    pub fn line_to_string(&self, line: Line) -> String {
+        let dot = |row: usize, col: usize| format!("{}{}", (b'a' + col as u8) as char, row + 1);
+        match line {
+            Line::Horizontal(row, col) => format!("{}-{}", dot(row, col), dot(row, col + 1)),
+            Line::Vertical(row, col) => format!("{}-{}", dot(row, col), dot(row + 1, col)),
+        }
+    }
+
+    fn is_drawn(&self, line: Line) -> bool {
+        match line {
+            Line::Horizontal(row, col) => self.horizontal[col + row * self.columns],
+            Line::Vertical(row, col) => self.vertical[col + row * (self.columns + 1)],
+        }
+    }
+
+    fn is_complete(&self, row: usize, col: usize) -> bool {
+        self.is_drawn(Line::Horizontal(row, col))
+            && self.is_drawn(Line::Horizontal(row + 1, col))
+            && self.is_drawn(Line::Vertical(row, col))
+            && self.is_drawn(Line::Vertical(row, col + 1))
+    }
+}
+
+fn lines_to_string(lines: &[bool], drawn: char, columns: usize) -> String {
+    lines
+        .chunks(columns)
+        .map(|row| row.iter().map(|l| if *l { drawn } else { '.' }).collect::<String>())
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+fn lines_from_string(string: &str, drawn: char, rows: usize, columns: usize) -> Result<Vec<bool>, WebErr> {
+    let parsed_rows = string.split('/').collect::<Vec<&str>>();
+    if parsed_rows.len() != rows || parsed_rows.iter().any(|r| r.chars().count() != columns) {
+        return Err(WebErr::BadReq(format!("lines {} are not {} rows of {} lines", string, rows, columns)));
+    }
+
+    parsed_rows
+        .iter()
+        .flat_map(|row| row.chars())
+        .map(|c| match c {
+            '.' => Ok(false),
+            c if c == drawn => Ok(true),
+            _ => Err(WebErr::BadReq(format!("line {} is not one of '{}' or '.'", c, drawn))),
+        })
+        .collect()
+}
@@ -0,0 +1,303 @@
+use super::rules::GameRules;
+use super::ttt::{
+    PlayerSymbol, check_board_status, symbols_to_string, symbols_from_string, side_from_str, side_to_str,
+};
+use crate::common::WebErr;
+use crate::models::general::{GameKey, MoveOutcome};
+
+
+// Largest number of rows or columns, so every column can be named by a single letter
+const MAX_SIZE: usize = 26;
+
+// A generic m,n,k-game, where players take turns placing a piece on an empty square of an m by n
+// board and the first to get k in a row (horizontally, vertically or diagonally) wins.
+#[derive(Clone)]
+pub struct MNKBoard {
+    board: Vec<PlayerSymbol>,
+    rows: usize,
+    columns: usize,
+    needed: usize,
+    // Whether lines longer than `needed` also win ("freestyle"), rather than only lines of exactly
+    // `needed` ("standard")
+    overline: bool,
+    first_to_move: bool,
+}
+
+// Gomoku, the m,n,k-game on a 15x15 board with five in a row needed.
+#[derive(Clone)]
+pub struct GomokuBoard(MNKBoard);
+
+impl MNKBoard {
+    pub fn new(rows: usize, columns: usize, needed: usize, overline: bool) -> Self {
+        MNKBoard {
+            board: vec![PlayerSymbol::Empty; rows * columns],
+            rows,
+            columns,
+            needed,
+            overline,
+            first_to_move: true,
+        }
+    }
+
+    // Parses a square in the format "h8" into a board index, with columns from 'a' and rows from 1.
+    fn parse_square(&self, new_move: &str) -> Result<usize, WebErr> {
+        let malformed = || WebErr::BadReq(format!(
+            "move {} is not a square from \"a1\" to \"{}{}\"",
+            new_move,
+            (b'a' + (self.columns - 1) as u8) as char,
+            self.rows,
+        ));
+
+        let mut chars = new_move.chars();
+        let col = match chars.next() {
+            Some(c @ 'a'..='z') => c as usize - 'a' as usize,
+            _ => return Err(malformed()),
+        };
+        let row_str = chars.as_str();
+        let row = row_str.parse::<usize>().or(Err(malformed()))?;
+
+        // Reject leading zeros and signs, so every square has exactly one notation
+        if col >= self.columns || row == 0 || row > self.rows || row.to_string() != row_str {
+            return Err(malformed());
+        }
+        Ok(col + (row - 1) * self.columns)
+    }
+
+    fn square_to_string(&self, index: usize) -> String {
+        format!("{}{}", (b'a' + (index % self.columns) as u8) as char, index / self.columns + 1)
+    }
+
+    fn check_square(&self, index: usize) -> Result<(), WebErr> {
+        if self.board[index] != PlayerSymbol::Empty {
+            return Err(WebErr::BadReq(format!("square {} is already taken", self.square_to_string(index))));
+        }
+        Ok(())
+    }
+
+    fn empty_squares(&self) -> Vec<String> {
+        (0..self.board.len())
+            .filter(|i| self.board[*i] == PlayerSymbol::Empty)
+            .map(|i| self.square_to_string(i))
+            .collect()
+    }
+
+    fn place(&mut self, index: usize, is_first: bool) -> MoveOutcome {
+        self.board[index] = if is_first {
+            PlayerSymbol::First
+        } else {
+            PlayerSymbol::Second
+        };
+        self.first_to_move = !is_first;
+
+        let move_num = self.board.iter().filter(|m| **m != PlayerSymbol::Empty).count();
+        self.check_status(index, move_num)
+    }
+
+    // `check_board_status` looks for any `needed` squares in a row, which counts overlines, so
+    // boards played with the standard rule measure the full length of each line instead.
+    fn check_status(&self, index: usize, move_num: usize) -> MoveOutcome {
+        if self.overline {
+            return check_board_status(index, move_num, &self.board, self.rows, self.columns, self.needed);
+        }
+
+        let symbol = self.board[index];
+        for (dc, dr) in [(1, 0), (0, 1), (1, 1), (1, -1)] {
+            let length = 1 + self.run_length(index, dc, dr) + self.run_length(index, -dc, -dr);
+            if length == self.needed {
+                return if symbol == PlayerSymbol::First {
+                    MoveOutcome::FirstWin
+                } else {
+                    MoveOutcome::SecondWin
+                };
+            }
+        }
+
+        if move_num == self.board.len() {
+            MoveOutcome::Draw
+        } else {
+            MoveOutcome::None
+        }
+    }
+
+    // Counts the squares in one direction from `index` that hold the same symbol as it.
+    fn run_length(&self, index: usize, dc: i32, dr: i32) -> usize {
+        let symbol = self.board[index];
+        let (mut col, mut row) = ((index % self.columns) as i32, (index / self.columns) as i32);
+        let mut length = 0;
+        loop {
+            col += dc;
+            row += dr;
+            if col < 0 || row < 0 || col >= self.columns as i32 || row >= self.rows as i32
+                || self.board[col as usize + row as usize * self.columns] != symbol
+            {
+                return length;
+            }
+            length += 1;
+        }
+    }
+
+    // Gets the status of the whole board, used to reject start positions that are already decided.
+    fn status(&self) -> MoveOutcome {
+        for i in 0..self.board.len() {
+            if self.board[i] == PlayerSymbol::Empty {
+                continue;
+            }
+
+            // Passing no moves played means only wins are checked for, not a full board
+            let status = self.check_status(i, 0);
+            if status != MoveOutcome::None {
+                return status;
+            }
+        }
+
+        if self.board.iter().all(|s| *s != PlayerSymbol::Empty) {
+            MoveOutcome::Draw
+        } else {
+            MoveOutcome::None
+        }
+    }
+
+    // Seeds this board from serialized rows, the side to move and an optional overline rule.
+    fn seed(mut self, rows: &str, side: &str, rule: Option<&str>, position: &str) -> Result<Self, WebErr> {
+        self.board = symbols_from_string(rows, self.rows, self.columns)?;
+        self.first_to_move = side_from_str(side)?;
+        self.overline = match rule {
+            Some("freestyle") | None => true,
+            Some("standard") => false,
+            Some(r) => return Err(WebErr::BadReq(format!("rule {} is not \"freestyle\" or \"standard\"", r))),
+        };
+
+        if self.status() != MoveOutcome::None {
+            return Err(WebErr::BadReq(format!("position {} has already been decided", position)));
+        }
+        Ok(self)
+    }
+
+    fn rule_str(&self) -> &'static str {
+        if self.overline { "freestyle" } else { "standard" }
+    }
+}
+
+impl Default for GomokuBoard {
+    fn default() -> Self {
+        GomokuBoard(MNKBoard::new(15, 15, 5, true))
+    }
+}
+
+impl GameRules for GomokuBoard {
+    // Board index
+    type Move = usize;
+
+    const KEY: GameKey = GameKey::Gomoku;
+
+    // Parses a gomoku position in the format "<15 rows from 1 to 15 separated by '/'> x standard":
+    // the side to move, optionally followed by the overline rule ("freestyle" if omitted).
+    fn from_position(position: &str) -> Result<Self, WebErr> {
+        let fields = position.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() != 2 && fields.len() != 3 {
+            return Err(WebErr::BadReq(format!("position {} is not in the format \"<rows> x standard\"", position)));
+        }
+
+        Ok(GomokuBoard(GomokuBoard::default().0.seed(fields[0], fields[1], fields.get(2).copied(), position)?))
+    }
+
+    fn parse_move(&self, new_move: &str) -> Result<usize, WebErr> {
+        self.0.parse_square(new_move)
+    }
+
+    // A gomoku move is invalid if its square has already been played.
+    fn check_move(&self, m: &usize) -> Result<(), WebErr> {
+        self.0.check_square(*m)
+    }
+
+    fn apply_move(&mut self, m: usize, is_first: bool) -> MoveOutcome {
+        self.0.place(m, is_first)
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.0.first_to_move
+    }
+
+    fn move_list(&self) -> Vec<String> {
+        self.0.empty_squares()
+    }
+
+    fn serialize(&self) -> String {
+        format!(
+            "{} {} {}",
+            symbols_to_string(&self.0.board, self.0.columns),
+            side_to_str(self.0.first_to_move),
+            self.0.rule_str(),
+        )
+    }
+}
+
+// Only there for `GameRules`, as m,n,k games always start from a position setting the board size.
+impl Default for MNKBoard {
+    fn default() -> Self {
+        GomokuBoard::default().0
+    }
+}
+
+impl GameRules for MNKBoard {
+    // Board index
+    type Move = usize;
+
+    const KEY: GameKey = GameKey::MNK;
+
+    const NEEDS_START_POS: bool = true;
+
+    // Parses an m,n,k position in the format "..../..../.... 3 x freestyle": m rows of n squares
+    // from row 1 up separated by '/', then k, the side to move and optionally the overline rule
+    // ("freestyle" if omitted). k may not be longer than the board's longer side.
+    fn from_position(position: &str) -> Result<Self, WebErr> {
+        let fields = position.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() != 3 && fields.len() != 4 {
+            return Err(WebErr::BadReq(format!("position {} is not in the format \"..../..../.... 3 x freestyle\"", position)));
+        }
+
+        let rows = fields[0].split('/').count();
+        let columns = fields[0].split('/').next().unwrap().chars().count();
+        if !(2..=MAX_SIZE).contains(&rows) || !(2..=MAX_SIZE).contains(&columns) {
+            return Err(WebErr::BadReq(format!("board must have between 2 and {} rows and columns", MAX_SIZE)));
+        }
+
+        let needed = fields[1].parse::<usize>()
+            .ok()
+            .filter(|k| (2..=rows.max(columns)).contains(k))
+            .ok_or(WebErr::BadReq(format!("k must be a number from 2 to {}", rows.max(columns))))?;
+
+        MNKBoard::new(rows, columns, needed, true).seed(fields[0], fields[2], fields.get(3).copied(), position)
+    }
+
+    fn parse_move(&self, new_move: &str) -> Result<usize, WebErr> {
+        self.parse_square(new_move)
+    }
+
+    // An m,n,k move is invalid if its square has already been played.
+    fn check_move(&self, m: &usize) -> Result<(), WebErr> {
+        self.check_square(*m)
+    }
+
+    fn apply_move(&mut self, m: usize, is_first: bool) -> MoveOutcome {
+        self.place(m, is_first)
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.first_to_move
+    }
+
+    fn move_list(&self) -> Vec<String> {
+        self.empty_squares()
+    }
+
+    fn serialize(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            symbols_to_string(&self.board, self.columns),
+            self.needed,
+            side_to_str(self.first_to_move),
+            self.rule_str(),
+        )
+    }
+}
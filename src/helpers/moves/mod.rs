@@ -1,3 +1,9 @@
+pub mod rules;
 pub mod ttt;
 pub mod uttt;
 pub mod c4;
+pub mod pc;
+pub mod mnk;
+pub mod othello;
+pub mod checkers;
+pub mod dab;
@@ -0,0 +1,195 @@
+use super::rules::GameRules;
+use super::ttt::{
+    PlayerSymbol, col_to_index, row_to_index, symbols_to_string, symbols_from_string, position_fields,
+    side_from_str, side_to_str,
+};
+use crate::common::WebErr;
+use crate::models::general::{GameKey, MoveOutcome};
+
+
+const DIRECTIONS: [(i32, i32); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+
+// A move in the format "d3", or "pass" when the side to move has no legal placement.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum OthelloMove {
+    Place(usize),
+    Pass,
+}
+
+// Squares are indexed from a1 (0) to h8 (63), rank by rank. The first player plays the dark discs
+// and moves first.
+#[derive(Clone)]
+pub struct OthelloBoard {
+    board: Vec<PlayerSymbol>,
+    first_to_move: bool,
+}
+
+impl Default for OthelloBoard {
+    fn default() -> Self {
+        let mut board = vec![PlayerSymbol::Empty; 64];
+        board[27] = PlayerSymbol::Second;
+        board[28] = PlayerSymbol::First;
+        board[35] = PlayerSymbol::First;
+        board[36] = PlayerSymbol::Second;
+
+        OthelloBoard {
+            board,
+            first_to_move: true,
+        }
+    }
+}
+
+impl GameRules for OthelloBoard {
+    type Move = OthelloMove;
+
+    const KEY: GameKey = GameKey::Othello;
+
+    // Parses an othello position in the format "<8 rows from 1 to 8 separated by '/'> x", the
+    // last field being the side to move.
+    fn from_position(position: &str) -> Result<Self, WebErr> {
+        let fields = position_fields(position, 2, "......../......../......../...ox.../...xo.../......../......../........ x")?;
+        let board = OthelloBoard {
+            board: symbols_from_string(fields[0], 8, 8)?,
+            first_to_move: side_from_str(fields[1])?,
+        };
+
+        if board.legal_moves(true).is_empty() && board.legal_moves(false).is_empty() {
+            return Err(WebErr::BadReq(format!("position {} has already been decided", position)));
+        }
+        Ok(board)
+    }
+
+    fn parse_move(&self, new_move: &str) -> Result<OthelloMove, WebErr> {
+        if new_move == "pass" {
+            return Ok(OthelloMove::Pass);
+        }
+
+        let mut chars = new_move.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(col @ 'a'..='h'), Some(row @ '1'..='8'), None) =>
+                Ok(OthelloMove::Place(col_to_index(col) + row_to_index(row) * 8)),
+            _ => Err(WebErr::BadReq(format!("move {} is not a square from \"a1\" to \"h8\" or \"pass\"", new_move))),
+        }
+    }
+
+    // An othello placement is invalid if its square is taken or it doesn't flank any of the
+    // opponent's discs. Passing is only allowed when there is no valid placement.
+    fn check_move(&self, m: &OthelloMove) -> Result<(), WebErr> {
+        match m {
+            OthelloMove::Place(index) => {
+                if self.board[*index] != PlayerSymbol::Empty {
+                    return Err(WebErr::BadReq(format!("square {} is already taken", square_to_string(*index))));
+                }
+                if self.flips(*index, self.first_to_move).is_empty() {
+                    return Err(WebErr::BadReq(format!("placing on {} does not flank any discs", square_to_string(*index))));
+                }
+            },
+            OthelloMove::Pass => {
+                if !self.legal_moves(self.first_to_move).is_empty() {
+                    return Err(WebErr::BadReq(format!("cannot pass while a placement is available")));
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn apply_move(&mut self, m: OthelloMove, is_first: bool) -> MoveOutcome {
+        self.first_to_move = !is_first;
+
+        let index = match m {
+            OthelloMove::Place(index) => index,
+            // A pass can't end the game, since the game ends as soon as neither player can move
+            OthelloMove::Pass => return MoveOutcome::None,
+        };
+
+        let symbol = if is_first {
+            PlayerSymbol::First
+        } else {
+            PlayerSymbol::Second
+        };
+        for flipped in self.flips(index, is_first) {
+            self.board[flipped] = symbol;
+        }
+        self.board[index] = symbol;
+
+        if !self.legal_moves(true).is_empty() || !self.legal_moves(false).is_empty() {
+            return MoveOutcome::None;
+        }
+
+        let first_discs = self.board.iter().filter(|s| **s == PlayerSymbol::First).count();
+        let second_discs = self.board.iter().filter(|s| **s == PlayerSymbol::Second).count();
+        match first_discs.cmp(&second_discs) {
+            std::cmp::Ordering::Greater => MoveOutcome::FirstWin,
+            std::cmp::Ordering::Less => MoveOutcome::SecondWin,
+            std::cmp::Ordering::Equal => MoveOutcome::Draw,
+        }
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.first_to_move
+    }
+
+    fn move_list(&self) -> Vec<String> {
+        let placements = self.legal_moves(self.first_to_move);
+        if placements.is_empty() {
+            return vec!["pass".to_string()];
+        }
+        placements.into_iter().map(square_to_string).collect()
+    }
+
+    fn serialize(&self) -> String {
+        format!("{} {}", symbols_to_string(&self.board, 8), side_to_str(self.first_to_move))
+    }
+}
+
+impl OthelloBoard {
+    // Gets all squares the given side can place a disc on, not including passing.
+    pub fn legal_moves(&self, first: bool) -> Vec<usize> {
+        (0..64)
+            .filter(|i| self.board[*i] == PlayerSymbol::Empty && !self.flips(*i, first).is_empty())
+            .collect()
+    }
+
+    // Gets the opponent's discs that would be flipped by the given side placing a disc on a
+    // square, i.e. every line of them that ends in one of the side's own discs.
+    fn flips(&self, index: usize, first: bool) -> Vec<usize> {
+        let (own, other) = if first {
+            (PlayerSymbol::First, PlayerSymbol::Second)
+        } else {
+            (PlayerSymbol::Second, PlayerSymbol::First)
+        };
+
+        let mut flipped = vec![];
+        for (dc, dr) in DIRECTIONS {
+            let mut line = vec![];
+            let mut square = index;
+            while let Some(next) = offset(square, dc, dr) {
+                if self.board[next] == other {
+                    line.push(next);
+                    square = next;
+                    continue;
+                }
+                if self.board[next] == own {
+                    flipped.append(&mut line);
+                }
+                break;
+            }
+        }
+        flipped
+    }
+}
+
+fn offset(square: usize, dc: i32, dr: i32) -> Option<usize> {
+    let col = (square % 8) as i32 + dc;
+    let row = (square / 8) as i32 + dr;
+
+    if (0..8).contains(&col) && (0..8).contains(&row) {
+        Some((col + row * 8) as usize)
+    } else {
+        None
+    }
+}
+
+fn square_to_string(square: usize) -> String {
+    format!("{}{}", (b'a' + (square % 8) as u8) as char, square / 8 + 1)
+}
@@ -0,0 +1,667 @@
+use super::rules::GameRules;
+use super::ttt::col_to_index;
+use crate::common::WebErr;
+use crate::models::general::{GameKey, MoveOutcome};
+
+
+// Pokémon Chess plays like standard chess, except every piece has a Pokémon type that decides
+// how a capture resolves:
+// 1. Neutral or super effective captures take the defending piece as usual
+// 2. Not very effective captures knock out both the attacking and the defending piece
+// 3. Captures that have no effect cannot be played
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum PieceKind {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum PokemonType {
+    Normal,
+    Ghost,
+    Psychic,
+    Rock,
+    Dragon,
+    Steel,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Effectiveness {
+    NoEffect,
+    NotVeryEffective,
+    Effective,
+    SuperEffective,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Piece {
+    pub kind: PieceKind,
+    pub first: bool,
+}
+
+// A move in the format "e2e4", with an optional promotion piece ("e7e8q").
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct PCMove {
+    pub from: usize,
+    pub to: usize,
+    pub promotion: Option<PieceKind>,
+}
+
+impl PieceKind {
+    pub fn pokemon_type(&self) -> PokemonType {
+        match self {
+            PieceKind::Pawn => PokemonType::Normal,
+            PieceKind::Knight => PokemonType::Ghost,
+            PieceKind::Bishop => PokemonType::Psychic,
+            PieceKind::Rook => PokemonType::Rock,
+            PieceKind::Queen => PokemonType::Dragon,
+            PieceKind::King => PokemonType::Steel,
+        }
+    }
+
+    fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_lowercase() {
+            'p' => Some(PieceKind::Pawn),
+            'n' => Some(PieceKind::Knight),
+            'b' => Some(PieceKind::Bishop),
+            'r' => Some(PieceKind::Rook),
+            'q' => Some(PieceKind::Queen),
+            'k' => Some(PieceKind::King),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            PieceKind::Pawn => 'p',
+            PieceKind::Knight => 'n',
+            PieceKind::Bishop => 'b',
+            PieceKind::Rook => 'r',
+            PieceKind::Queen => 'q',
+            PieceKind::King => 'k',
+        }
+    }
+}
+
+impl PokemonType {
+    // Gets the effectiveness of an attack by this type against a defending type, following the
+    // classic type chart.
+    pub fn effectiveness(&self, defender: PokemonType) -> Effectiveness {
+        match (self, defender) {
+            (PokemonType::Normal, PokemonType::Ghost)
+            | (PokemonType::Ghost, PokemonType::Normal) => Effectiveness::NoEffect,
+            (PokemonType::Ghost, PokemonType::Ghost)
+            | (PokemonType::Ghost, PokemonType::Psychic)
+            | (PokemonType::Dragon, PokemonType::Dragon)
+            | (PokemonType::Steel, PokemonType::Rock) => Effectiveness::SuperEffective,
+            (PokemonType::Normal, PokemonType::Rock)
+            | (PokemonType::Psychic, PokemonType::Psychic)
+            | (_, PokemonType::Steel) => Effectiveness::NotVeryEffective,
+            _ => Effectiveness::Effective,
+        }
+    }
+}
+
+impl Piece {
+    fn effectiveness_against(&self, defender: &Piece) -> Effectiveness {
+        self.kind.pokemon_type().effectiveness(defender.kind.pokemon_type())
+    }
+
+    fn to_char(&self) -> char {
+        if self.first {
+            self.kind.to_char().to_ascii_uppercase()
+        } else {
+            self.kind.to_char()
+        }
+    }
+}
+
+const KNIGHT_OFFSETS: [(i32, i32); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_OFFSETS: [(i32, i32); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+const ROOK_DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const BISHOP_DIRECTIONS: [(i32, i32); 4] = [(1, 1), (-1, 1), (-1, -1), (1, -1)];
+
+// Castling rook squares, in the same order as `PCBoard::castling`
+const CASTLING_CORNERS: [usize; 4] = [7, 0, 63, 56];
+
+// Squares are indexed from a1 (0) to h8 (63), rank by rank, with the first player starting on
+// ranks 1 and 2.
+#[derive(Clone)]
+pub struct PCBoard {
+    squares: Vec<Option<Piece>>,
+    first_to_move: bool,
+    // first kingside, first queenside, second kingside, second queenside
+    castling: [bool; 4],
+    en_passant: Option<usize>,
+    halfmove_clock: usize,
+}
+
+impl Default for PCBoard {
+    fn default() -> Self {
+        let back_rank = [
+            PieceKind::Rook, PieceKind::Knight, PieceKind::Bishop, PieceKind::Queen,
+            PieceKind::King, PieceKind::Bishop, PieceKind::Knight, PieceKind::Rook,
+        ];
+
+        let mut squares = vec![None; 64];
+        for (file, kind) in back_rank.iter().enumerate() {
+            squares[file] = Some(Piece { kind: *kind, first: true });
+            squares[file + 8] = Some(Piece { kind: PieceKind::Pawn, first: true });
+            squares[file + 48] = Some(Piece { kind: PieceKind::Pawn, first: false });
+            squares[file + 56] = Some(Piece { kind: *kind, first: false });
+        }
+
+        PCBoard {
+            squares,
+            first_to_move: true,
+            castling: [true; 4],
+            en_passant: None,
+            halfmove_clock: 0,
+        }
+    }
+}
+
+impl GameRules for PCBoard {
+    type Move = PCMove;
+
+    const KEY: GameKey = GameKey::PC;
+
+    // Parses a pc position in FEN. The halfmove clock and fullmove number may be omitted, and the
+    // fullmove number is ignored.
+    fn from_position(position: &str) -> Result<Self, WebErr> {
+        let invalid = |reason: &str| WebErr::BadReq(format!("position {} is not valid FEN: {}", position, reason));
+        let fields = position.split_whitespace().collect::<Vec<&str>>();
+        if !(4..=6).contains(&fields.len()) {
+            return Err(invalid("expected 4 to 6 fields"));
+        }
+
+        let ranks = fields[0].split('/').collect::<Vec<&str>>();
+        if ranks.len() != 8 {
+            return Err(invalid("expected 8 ranks"));
+        }
+        let mut squares = vec![None; 64];
+        for (i, rank) in ranks.iter().enumerate() {
+            let mut file = 0;
+            for c in rank.chars() {
+                if let Some(empty) = c.to_digit(10) {
+                    file += empty as usize;
+                    continue;
+                }
+                let kind = PieceKind::from_char(c).ok_or_else(|| invalid("unknown piece"))?;
+                if file >= 8 {
+                    return Err(invalid("rank has more than 8 squares"));
+                }
+                squares[file + (7 - i) * 8] = Some(Piece { kind, first: c.is_ascii_uppercase() });
+                file += 1;
+            }
+            if file != 8 {
+                return Err(invalid("rank does not have 8 squares"));
+            }
+        }
+
+        let first_to_move = match fields[1] {
+            "w" => true,
+            "b" => false,
+            _ => return Err(invalid("side to move is not \"w\" or \"b\"")),
+        };
+
+        let mut castling = [false; 4];
+        if fields[2] != "-" {
+            for c in fields[2].chars() {
+                let i = "KQkq".find(c).ok_or_else(|| invalid("unknown castling right"))?;
+                castling[i] = true;
+            }
+        }
+
+        let en_passant = match fields[3] {
+            "-" => None,
+            square => {
+                let chars = square.chars().collect::<Vec<char>>();
+                if chars.len() != 2 {
+                    return Err(invalid("en passant square is malformed"));
+                }
+                Some(parse_square(chars[0], chars[1]).ok_or_else(|| invalid("en passant square is malformed"))?)
+            },
+        };
+
+        let halfmove_clock = match fields.get(4) {
+            Some(clock) => clock.parse::<usize>().or(Err(invalid("halfmove clock is not a number")))?,
+            None => 0,
+        };
+
+        let board = PCBoard { squares, first_to_move, castling, en_passant, halfmove_clock };
+        board.check_position().map_err(invalid)?;
+        Ok(board)
+    }
+
+    fn parse_move(&self, new_move: &str) -> Result<PCMove, WebErr> {
+        let chars = new_move.chars().collect::<Vec<char>>();
+        let malformed = || WebErr::BadReq(format!("move {} is not in the format \"e2e4\" or \"e7e8q\"", new_move));
+        if chars.len() != 4 && chars.len() != 5 {
+            return Err(malformed());
+        }
+
+        let promotion = match chars.get(4) {
+            Some(c) => Some(PieceKind::from_char(*c)
+                .filter(|k| !matches!(k, PieceKind::Pawn | PieceKind::King))
+                .ok_or_else(malformed)?),
+            None => None,
+        };
+
+        Ok(PCMove {
+            from: parse_square(chars[0], chars[1]).ok_or_else(malformed)?,
+            to: parse_square(chars[2], chars[3]).ok_or_else(malformed)?,
+            promotion,
+        })
+    }
+
+    // A pc move is invalid if it is not one of the legal moves of the side to move.
+    fn check_move(&self, m: &PCMove) -> Result<(), WebErr> {
+        if !self.squares[m.from].is_some_and(|p| p.first == self.first_to_move) {
+            return Err(WebErr::BadReq(format!("no piece of the side to move on {}", square_to_string(m.from))));
+        }
+        if !self.legal_moves(self.first_to_move).contains(m) {
+            return Err(WebErr::BadReq(format!(
+                "moving from {} to {} is not legal",
+                square_to_string(m.from),
+                square_to_string(m.to),
+            )));
+        }
+        Ok(())
+    }
+
+    fn apply_move(&mut self, m: PCMove, is_first: bool) -> MoveOutcome {
+        self.make_move(&m);
+
+        if self.legal_moves(!is_first).is_empty() {
+            return if !self.in_check(!is_first) {
+                MoveOutcome::Draw
+            } else if is_first {
+                MoveOutcome::FirstWin
+            } else {
+                MoveOutcome::SecondWin
+            };
+        }
+
+        // Fifty-move rule
+        if self.halfmove_clock >= 100 || self.has_insufficient_material() {
+            return MoveOutcome::Draw;
+        }
+
+        MoveOutcome::None
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.first_to_move
+    }
+
+    fn move_list(&self) -> Vec<String> {
+        self.legal_moves(self.first_to_move).iter().map(|m| format!(
+            "{}{}{}",
+            square_to_string(m.from),
+            square_to_string(m.to),
+            m.promotion.map(|k| k.to_char().to_string()).unwrap_or_default(),
+        )).collect()
+    }
+
+    // Serializes the board in FEN, without the fullmove number.
+    fn serialize(&self) -> String {
+        let placement = (0..8).rev().map(|rank| {
+            let mut row = String::new();
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.squares[file + rank * 8] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            row.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        row.push(piece.to_char());
+                    },
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                row.push_str(&empty.to_string());
+            }
+            row
+        }).collect::<Vec<String>>().join("/");
+
+        let castling = ['K', 'Q', 'k', 'q'].iter()
+            .zip(self.castling.iter())
+            .filter(|(_, allowed)| **allowed)
+            .map(|(c, _)| *c)
+            .collect::<String>();
+
+        format!(
+            "{} {} {} {} {}",
+            placement,
+            if self.first_to_move { "w" } else { "b" },
+            if castling.is_empty() { "-" } else { castling.as_str() },
+            self.en_passant.map(square_to_string).unwrap_or("-".to_string()),
+            self.halfmove_clock,
+        )
+    }
+}
+
+impl PCBoard {
+    // Gets all moves the given side can make without leaving its own king in check.
+    pub fn legal_moves(&self, first: bool) -> Vec<PCMove> {
+        self.pseudo_legal_moves(first).into_iter().filter(|m| {
+            let mut board = self.clone();
+            board.make_move(m);
+            !board.in_check(first)
+        }).collect()
+    }
+
+    pub fn in_check(&self, first: bool) -> bool {
+        match self.squares.iter().position(|s| *s == Some(Piece { kind: PieceKind::King, first })) {
+            Some(king) => self.is_attacked(king, !first),
+            None => false,
+        }
+    }
+
+    // Asserts that a parsed position could be reached in a game that is still in progress,
+    // returning the reason if it could not.
+    fn check_position(&self) -> Result<(), &'static str> {
+        for first in [true, false] {
+            let kings = self.squares.iter().filter(|s| **s == Some(Piece { kind: PieceKind::King, first })).count();
+            if kings != 1 {
+                return Err("each side must have exactly one king");
+            }
+        }
+        if (0..8).chain(56..64).any(|i| self.squares[i].is_some_and(|p| p.kind == PieceKind::Pawn)) {
+            return Err("pawns cannot stand on the first or last rank");
+        }
+
+        for (i, corner) in CASTLING_CORNERS.iter().enumerate() {
+            let first = i < 2;
+            let home = if first { 4 } else { 60 };
+            if self.castling[i] && (
+                self.squares[home] != Some(Piece { kind: PieceKind::King, first })
+                    || self.squares[*corner] != Some(Piece { kind: PieceKind::Rook, first })
+            ) {
+                return Err("castling rights require the king and rook on their starting squares");
+            }
+        }
+
+        if let Some(square) = self.en_passant {
+            let rank = if self.first_to_move { 5 } else { 2 };
+            let pawn = if self.first_to_move { square.wrapping_sub(8) } else { square + 8 };
+            if square / 8 != rank || self.squares[pawn] != Some(Piece { kind: PieceKind::Pawn, first: !self.first_to_move }) {
+                return Err("en passant square does not follow a double pawn push");
+            }
+        }
+
+        if self.in_check(!self.first_to_move) {
+            return Err("the side not to move is in check");
+        }
+        if self.legal_moves(self.first_to_move).is_empty() || self.has_insufficient_material() {
+            return Err("the game has already been decided");
+        }
+        Ok(())
+    }
+
+    // Updates the board with a move, without checking for the end of the game.
+    fn make_move(&mut self, m: &PCMove) {
+        let piece = self.squares[m.from].unwrap();
+
+        // En passant captures take the pawn behind the target square
+        let captured_square = if piece.kind == PieceKind::Pawn && self.en_passant == Some(m.to) && self.squares[m.to].is_none() {
+            if piece.first { m.to - 8 } else { m.to + 8 }
+        } else {
+            m.to
+        };
+        let captured = self.squares[captured_square];
+
+        self.squares[m.from] = None;
+        self.squares[captured_square] = None;
+        if !captured.is_some_and(|c| piece.effectiveness_against(&c) == Effectiveness::NotVeryEffective) {
+            self.squares[m.to] = Some(Piece {
+                kind: m.promotion.unwrap_or(piece.kind),
+                first: piece.first,
+            });
+        }
+
+        // Castling also moves the rook to the other side of the king
+        if piece.kind == PieceKind::King && m.to.abs_diff(m.from) == 2 {
+            let (rook_from, rook_to) = if m.to > m.from {
+                (m.from + 3, m.from + 1)
+            } else {
+                (m.from - 4, m.from - 1)
+            };
+            self.squares[rook_to] = self.squares[rook_from].take();
+        }
+
+        if piece.kind == PieceKind::King {
+            let rights = if piece.first { 0..2 } else { 2..4 };
+            for i in rights {
+                self.castling[i] = false;
+            }
+        }
+        for (i, corner) in CASTLING_CORNERS.iter().enumerate() {
+            if m.from == *corner || m.to == *corner {
+                self.castling[i] = false;
+            }
+        }
+
+        self.en_passant = if piece.kind == PieceKind::Pawn && m.to.abs_diff(m.from) == 16 {
+            Some((m.from + m.to) / 2)
+        } else {
+            None
+        };
+        self.halfmove_clock = if piece.kind == PieceKind::Pawn || captured.is_some() {
+            0
+        } else {
+            self.halfmove_clock + 1
+        };
+        self.first_to_move = !piece.first;
+    }
+
+    // Gets all moves the given side can make, ignoring whether they leave its king in check.
+    fn pseudo_legal_moves(&self, first: bool) -> Vec<PCMove> {
+        let mut moves = vec![];
+        for from in 0..64 {
+            let piece = match self.squares[from] {
+                Some(p) if p.first == first => p,
+                _ => continue,
+            };
+
+            match piece.kind {
+                PieceKind::Pawn => self.pawn_moves(from, piece, &mut moves),
+                PieceKind::Knight => self.step_moves(from, piece, &KNIGHT_OFFSETS, &mut moves),
+                PieceKind::Bishop => self.slide_moves(from, piece, &BISHOP_DIRECTIONS, &mut moves),
+                PieceKind::Rook => self.slide_moves(from, piece, &ROOK_DIRECTIONS, &mut moves),
+                PieceKind::Queen => {
+                    self.slide_moves(from, piece, &ROOK_DIRECTIONS, &mut moves);
+                    self.slide_moves(from, piece, &BISHOP_DIRECTIONS, &mut moves);
+                },
+                PieceKind::King => {
+                    self.step_moves(from, piece, &KING_OFFSETS, &mut moves);
+                    self.castling_moves(from, piece, &mut moves);
+                },
+            }
+        }
+        moves
+    }
+
+    // Gets whether a piece can move onto a square, meaning the square is empty or holds an enemy
+    // piece the mover's type can affect. Kings also can't make not very effective captures, since
+    // they would knock themselves out.
+    fn can_land(&self, piece: Piece, to: usize) -> bool {
+        match self.squares[to] {
+            None => true,
+            Some(target) => target.first != piece.first && match piece.effectiveness_against(&target) {
+                Effectiveness::NoEffect => false,
+                Effectiveness::NotVeryEffective => piece.kind != PieceKind::King,
+                _ => true,
+            },
+        }
+    }
+
+    fn step_moves(&self, from: usize, piece: Piece, offsets: &[(i32, i32)], moves: &mut Vec<PCMove>) {
+        for (df, dr) in offsets {
+            if let Some(to) = offset(from, *df, *dr) {
+                if self.can_land(piece, to) {
+                    moves.push(PCMove { from, to, promotion: None });
+                }
+            }
+        }
+    }
+
+    fn slide_moves(&self, from: usize, piece: Piece, directions: &[(i32, i32)], moves: &mut Vec<PCMove>) {
+        for (df, dr) in directions {
+            let mut square = from;
+            while let Some(to) = offset(square, *df, *dr) {
+                if self.can_land(piece, to) {
+                    moves.push(PCMove { from, to, promotion: None });
+                }
+                if self.squares[to].is_some() {
+                    break;
+                }
+                square = to;
+            }
+        }
+    }
+
+    fn pawn_moves(&self, from: usize, piece: Piece, moves: &mut Vec<PCMove>) {
+        let dir = if piece.first { 1 } else { -1 };
+        let start_rank = if piece.first { 1 } else { 6 };
+        let mut targets = vec![];
+
+        if let Some(to) = offset(from, 0, dir) {
+            if self.squares[to].is_none() {
+                targets.push(to);
+                if from / 8 == start_rank {
+                    if let Some(double) = offset(from, 0, 2 * dir) {
+                        if self.squares[double].is_none() {
+                            targets.push(double);
+                        }
+                    }
+                }
+            }
+        }
+
+        for df in [-1, 1] {
+            if let Some(to) = offset(from, df, dir) {
+                if self.squares[to].is_some() && self.can_land(piece, to) || self.en_passant == Some(to) {
+                    targets.push(to);
+                }
+            }
+        }
+
+        for to in targets {
+            if to / 8 == 0 || to / 8 == 7 {
+                for kind in [PieceKind::Queen, PieceKind::Rook, PieceKind::Bishop, PieceKind::Knight] {
+                    moves.push(PCMove { from, to, promotion: Some(kind) });
+                }
+            } else {
+                moves.push(PCMove { from, to, promotion: None });
+            }
+        }
+    }
+
+    fn castling_moves(&self, from: usize, piece: Piece, moves: &mut Vec<PCMove>) {
+        let (kingside, queenside, home) = if piece.first { (0, 1, 4) } else { (2, 3, 60) };
+        if from != home || self.is_attacked(home, !piece.first) {
+            return;
+        }
+
+        if self.castling[kingside]
+            && self.squares[home + 1].is_none()
+            && self.squares[home + 2].is_none()
+            && !self.is_attacked(home + 1, !piece.first)
+        {
+            moves.push(PCMove { from, to: home + 2, promotion: None });
+        }
+        if self.castling[queenside]
+            && (1..=3).all(|i| self.squares[home - i].is_none())
+            && !self.is_attacked(home - 1, !piece.first)
+        {
+            moves.push(PCMove { from, to: home - 2, promotion: None });
+        }
+    }
+
+    // Gets whether the given side could capture a king standing on a square.
+    fn is_attacked(&self, square: usize, by_first: bool) -> bool {
+        let king = Piece { kind: PieceKind::King, first: !by_first };
+        let attacks = |from: usize, kinds: &[PieceKind]| self.squares[from].is_some_and(|p|
+            p.first == by_first
+                && kinds.contains(&p.kind)
+                && p.effectiveness_against(&king) != Effectiveness::NoEffect
+        );
+
+        let pawn_dir = if by_first { -1 } else { 1 };
+        if [-1, 1].iter().any(|df| offset(square, *df, pawn_dir).is_some_and(|from| attacks(from, &[PieceKind::Pawn]))) {
+            return true;
+        }
+        if KNIGHT_OFFSETS.iter().any(|(df, dr)| offset(square, *df, *dr).is_some_and(|from| attacks(from, &[PieceKind::Knight]))) {
+            return true;
+        }
+
+        // Kings may never stand next to each other, even though they can't capture one another
+        if KING_OFFSETS.iter().any(|(df, dr)| offset(square, *df, *dr).is_some_and(|from|
+            self.squares[from] == Some(Piece { kind: PieceKind::King, first: by_first })
+        )) {
+            return true;
+        }
+
+        for (directions, kinds) in [
+            (ROOK_DIRECTIONS, [PieceKind::Rook, PieceKind::Queen]),
+            (BISHOP_DIRECTIONS, [PieceKind::Bishop, PieceKind::Queen]),
+        ] {
+            for (df, dr) in directions {
+                let mut current = square;
+                while let Some(from) = offset(current, df, dr) {
+                    if self.squares[from].is_some() {
+                        if attacks(from, &kinds) {
+                            return true;
+                        }
+                        break;
+                    }
+                    current = from;
+                }
+            }
+        }
+
+        false
+    }
+
+    // Gets whether neither side can possibly checkmate, i.e. only kings and at most one minor
+    // piece remain.
+    fn has_insufficient_material(&self) -> bool {
+        let others = self.squares.iter()
+            .flatten()
+            .filter(|p| p.kind != PieceKind::King)
+            .collect::<Vec<&Piece>>();
+
+        others.len() == 0
+            || others.len() == 1 && matches!(others[0].kind, PieceKind::Knight | PieceKind::Bishop)
+    }
+}
+
+fn offset(square: usize, df: i32, dr: i32) -> Option<usize> {
+    let file = (square % 8) as i32 + df;
+    let rank = (square / 8) as i32 + dr;
+
+    if (0..8).contains(&file) && (0..8).contains(&rank) {
+        Some((file + rank * 8) as usize)
+    } else {
+        None
+    }
+}
+
+fn parse_square(file: char, rank: char) -> Option<usize> {
+    if !matches!(file, 'a'..='h') || !matches!(rank, '1'..='8') {
+        return None;
+    }
+    Some(col_to_index(file) + (rank.to_digit(10).unwrap() as usize - 1) * 8)
+}
+
+fn square_to_string(square: usize) -> String {
+    format!("{}{}", (b'a' + (square % 8) as u8) as char, square / 8 + 1)
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::common::WebErr;
+use crate::models::general::{GameKey, MoveOutcome};
+use super::c4::C4Board;
+use super::checkers::CheckersBoard;
+use super::dab::DABBoard;
+use super::mnk::{GomokuBoard, MNKBoard};
+use super::othello::OthelloBoard;
+use super::pc::PCBoard;
+use super::ttt::TTTBoard;
+use super::uttt::UTTTBoard;
+
+
+// Rules for a single game. An implementor holds the full board state of one game, and is
+// registered by its `GameKey` in `register_games` so the `LumberMill` can dispatch to it.
+pub trait GameRules: Default + Send + Sync + 'static {
+    type Move;
+
+    const KEY: GameKey;
+
+    // Whether games can only start from a custom start position, e.g. because it sets the board size.
+    const NEEDS_START_POS: bool = false;
+
+    // Parses a board from a custom start position, in the same notation produced by `serialize`,
+    // returning a `WebErr::BadReq` describing why the position is invalid.
+    fn from_position(position: &str) -> Result<Self, WebErr>;
+
+    // Parses a move string into this game's move type, returning a `WebErr::BadReq` describing
+    // why it is malformed.
+    fn parse_move(&self, new_move: &str) -> Result<Self::Move, WebErr>;
+
+    // Asserts that a parsed move is legal on the current board, returning a `WebErr::BadReq`
+    // describing why it is not.
+    fn check_move(&self, m: &Self::Move) -> Result<(), WebErr>;
+
+    // Applies a legal move to the board, returning the resultant `MoveOutcome`.
+    fn apply_move(&mut self, m: Self::Move, is_first: bool) -> MoveOutcome;
+
+    // Gets whether the first player is the side to move.
+    fn first_to_move(&self) -> bool;
+
+    // Lists every legal move of the side to move, in the same notation accepted by `parse_move`.
+    fn move_list(&self) -> Vec<String>;
+
+    // Gets the index of the board the next move must be played on, for games made of several
+    // boards.
+    fn active_board(&self) -> Option<usize> {
+        None
+    }
+
+    // Serializes the current board state, including the side to move.
+    fn serialize(&self) -> String;
+}
+
+// Object-safe view of a `GameRules` implementor, so boards of different games can share a map.
+pub trait GameBoard: Send + Sync {
+    fn validate_move(&self, new_move: &str) -> Result<(), WebErr>;
+    fn process_move(&mut self, new_move: &str, is_first: bool) -> Result<MoveOutcome, WebErr>;
+    fn first_to_move(&self) -> bool;
+    fn legal_moves(&self) -> Vec<String>;
+    fn active_board(&self) -> Option<usize>;
+    fn state(&self) -> String;
+}
+
+impl<T: GameRules> GameBoard for T {
+    fn validate_move(&self, new_move: &str) -> Result<(), WebErr> {
+        self.check_move(&self.parse_move(new_move)?)
+    }
+
+    fn process_move(&mut self, new_move: &str, is_first: bool) -> Result<MoveOutcome, WebErr> {
+        let m = self.parse_move(new_move)?;
+        self.check_move(&m)?;
+        Ok(self.apply_move(m, is_first))
+    }
+
+    fn first_to_move(&self) -> bool {
+        GameRules::first_to_move(self)
+    }
+
+    fn legal_moves(&self) -> Vec<String> {
+        self.move_list()
+    }
+
+    fn active_board(&self) -> Option<usize> {
+        GameRules::active_board(self)
+    }
+
+    fn state(&self) -> String {
+        self.serialize()
+    }
+}
+
+type BoardConstructor = fn(Option<&str>) -> Result<Box<dyn GameBoard>, WebErr>;
+
+static REGISTRY: OnceLock<HashMap<GameKey, BoardConstructor>> = OnceLock::new();
+
+// Registers the rules of every playable game. Adding a game only requires a line here.
+fn register_games(registry: &mut HashMap<GameKey, BoardConstructor>) {
+    register::<TTTBoard>(registry);
+    register::<UTTTBoard>(registry);
+    register::<C4Board>(registry);
+    register::<PCBoard>(registry);
+    register::<GomokuBoard>(registry);
+    register::<MNKBoard>(registry);
+    register::<OthelloBoard>(registry);
+    register::<CheckersBoard>(registry);
+    register::<DABBoard>(registry);
+}
+
+fn register<T: GameRules>(registry: &mut HashMap<GameKey, BoardConstructor>) {
+    registry.insert(T::KEY, new_board::<T>);
+}
+
+fn new_board<T: GameRules>(start_pos: Option<&str>) -> Result<Box<dyn GameBoard>, WebErr> {
+    Ok(Box::new(match start_pos {
+        Some(position) => T::from_position(position)?,
+        None if T::NEEDS_START_POS => return Err(WebErr::BadReq(format!("game {} needs a start position", T::KEY))),
+        None => T::default(),
+    }))
+}
+
+// Creates a board for the given game, seeded from a custom start position if one is given.
+// Returns a `WebErr::BadReq` if the game has no registered rules or the position is invalid.
+pub fn create_board(key: &GameKey, start_pos: Option<&str>) -> Result<Box<dyn GameBoard>, WebErr> {
+    let constructor = REGISTRY
+        .get_or_init(|| {
+            let mut registry = HashMap::new();
+            register_games(&mut registry);
+            registry
+        })
+        .get(key)
+        .ok_or(WebErr::BadReq(format!("game {} is not supported", key)))?;
+
+    constructor(start_pos)
+}
@@ -1,5 +1,8 @@
+use std::fmt::{self, Display};
 use log::debug;
-use crate::models::general::MoveOutcome;
+use crate::common::WebErr;
+use crate::models::general::{GameKey, MoveOutcome};
+use super::rules::GameRules;
 
 
 #[derive(PartialEq, Clone, Copy)]
@@ -7,28 +10,111 @@ pub enum PlayerSymbol {
     First, Second, Empty
 }
 
-// Validates a ttt move. A move is invalid if:
-// 1. It is not in the correct format ("a1")
-// 2. It has already been played
-pub fn validate_ttt_move(new_move: &str, moves: Vec<&str>) -> bool {
-    !moves.contains(&new_move)
-        && new_move.chars().nth(0).is_some_and(|c| matches!(c, 'a'..='c'))
-        && new_move.chars().nth(1).is_some_and(|c| c.is_digit(10))
+// A square on a 3x3 board, parsed from the format "a1".
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Coord {
+    pub col: usize,
+    pub row: usize,
 }
 
-pub fn process_ttt_move(new_move: &str, board: &mut Vec<PlayerSymbol>, is_first: bool) -> MoveOutcome {
-    let m = col_to_index(new_move.chars().nth(0).unwrap())
-        + row_to_index(new_move.chars().nth(1).unwrap()) * 3;
+impl Coord {
+    pub fn parse(string: &str) -> Result<Self, WebErr> {
+        let mut chars = string.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(col @ 'a'..='c'), Some(row @ '1'..='3'), None) => Ok(Coord {
+                col: col_to_index(col),
+                row: row_to_index(row),
+            }),
+            _ => Err(WebErr::BadReq(format!("square {} is not in the format \"a1\" (a-c, 1-3)", string))),
+        }
+    }
 
-    board[m] = if is_first {
-        PlayerSymbol::First
-    } else {
-        PlayerSymbol::Second
-    };
+    pub fn index(&self) -> usize {
+        self.col + self.row * 3
+    }
+}
 
-    // TODO: better move num calc?
-    let move_num = board.iter().filter(|m| **m != PlayerSymbol::Empty).count();
-    check_board_status(m, move_num, board, 3, 3, 3)
+impl Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.col as u8) as char, self.row + 1)
+    }
+}
+
+#[derive(Clone)]
+pub struct TTTBoard {
+    board: Vec<PlayerSymbol>,
+    first_to_move: bool,
+}
+
+impl Default for TTTBoard {
+    fn default() -> Self {
+        TTTBoard {
+            board: vec![PlayerSymbol::Empty; 9],
+            first_to_move: true,
+        }
+    }
+}
+
+impl GameRules for TTTBoard {
+    type Move = Coord;
+
+    const KEY: GameKey = GameKey::TTT;
+
+    // Parses a ttt position in the format "x.o/.x./... o": rows from 1 to 3 separated by '/',
+    // followed by the side to move.
+    fn from_position(position: &str) -> Result<Self, WebErr> {
+        let fields = position_fields(position, 2, "x.o/.x./... o")?;
+        let board = TTTBoard {
+            board: symbols_from_string(fields[0], 3, 3)?,
+            first_to_move: side_from_str(fields[1])?,
+        };
+
+        if get_board_status(&board.board, 3, 3, 3) != MoveOutcome::None {
+            return Err(WebErr::BadReq(format!("position {} has already been decided", position)));
+        }
+        Ok(board)
+    }
+
+    fn parse_move(&self, new_move: &str) -> Result<Coord, WebErr> {
+        Coord::parse(new_move)
+    }
+
+    // A ttt move is invalid if its square has already been played.
+    fn check_move(&self, m: &Coord) -> Result<(), WebErr> {
+        if self.board[m.index()] != PlayerSymbol::Empty {
+            return Err(WebErr::BadReq(format!("square {} is already taken", m)));
+        }
+        Ok(())
+    }
+
+    fn apply_move(&mut self, m: Coord, is_first: bool) -> MoveOutcome {
+        let index = m.index();
+        self.board[index] = if is_first {
+            PlayerSymbol::First
+        } else {
+            PlayerSymbol::Second
+        };
+
+        // TODO: better move num calc?
+        let move_num = self.board.iter().filter(|m| **m != PlayerSymbol::Empty).count();
+        self.first_to_move = !is_first;
+        check_board_status(index, move_num, &self.board, 3, 3, 3)
+    }
+
+    fn first_to_move(&self) -> bool {
+        self.first_to_move
+    }
+
+    fn move_list(&self) -> Vec<String> {
+        (0..9)
+            .filter(|i| self.board[*i] == PlayerSymbol::Empty)
+            .map(|i| Coord { col: i % 3, row: i / 3 }.to_string())
+            .collect()
+    }
+
+    fn serialize(&self) -> String {
+        format!("{} {}", symbols_to_string(&self.board, 3), side_to_str(self.first_to_move))
+    }
 }
 
 pub fn check_board_status(m: usize, move_num: usize, board: &Vec<PlayerSymbol>, rows: usize, columns: usize, needed: usize) -> MoveOutcome {
@@ -88,22 +174,25 @@ pub fn check_board_status(m: usize, move_num: usize, board: &Vec<PlayerSymbol>,
         col_start - row_num
     };
 
-    if rows.min(columns) - row_num.abs_diff(col_start) >= needed {
-        for i in (diag_start..=m).step_by(columns + 1) {
-            let mut cond = board[i] != PlayerSymbol::Empty;
-            for j in 1..needed {
-                let index = i + (j * (columns + 1));
-                cond = cond && index < board.len() && board[i] == board[index];
-            }
+    for i in (diag_start..=m).step_by(columns + 1) {
+        // Skip lines that would wrap past the right edge or run off the top of the board
+        if i % columns + needed > columns || i / columns + needed > rows {
+            continue;
+        }
 
-            if cond {
-                debug!("won on diagonal");
-                return if board[i] == PlayerSymbol::First {
-                    MoveOutcome::FirstWin
-                } else {
-                    MoveOutcome::SecondWin
-                };
-            }
+        let mut cond = board[i] != PlayerSymbol::Empty;
+        for j in 1..needed {
+            let index = i + (j * (columns + 1));
+            cond = cond && index < board.len() && board[i] == board[index];
+        }
+
+        if cond {
+            debug!("won on diagonal");
+            return if board[i] == PlayerSymbol::First {
+                MoveOutcome::FirstWin
+            } else {
+                MoveOutcome::SecondWin
+            };
         }
     }
 
@@ -117,6 +206,11 @@ pub fn check_board_status(m: usize, move_num: usize, board: &Vec<PlayerSymbol>,
     // TODO: row check?
     if anti_diag_start >= needed - 1 {
         for i in (anti_diag_start..=m).step_by(columns - 1) {
+            // Skip lines that would wrap past the left edge
+            if i % columns < needed - 1 {
+                continue;
+            }
+
             let mut cond = board[i] != PlayerSymbol::Empty;
             for j in 1..needed {
                 let index = i + (j * (columns - 1));
@@ -142,6 +236,27 @@ pub fn check_board_status(m: usize, move_num: usize, board: &Vec<PlayerSymbol>,
     MoveOutcome::None
 }
 
+// Gets the status of a whole board, rather than only the lines through the last move.
+pub fn get_board_status(board: &Vec<PlayerSymbol>, rows: usize, columns: usize, needed: usize) -> MoveOutcome {
+    for i in 0..board.len() {
+        if board[i] == PlayerSymbol::Empty {
+            continue;
+        }
+
+        // Passing no moves played means only wins are checked for, not a full board
+        let status = check_board_status(i, 0, board, rows, columns, needed);
+        if status != MoveOutcome::None {
+            return status;
+        }
+    }
+
+    if board.iter().all(|s| *s != PlayerSymbol::Empty) {
+        MoveOutcome::Draw
+    } else {
+        MoveOutcome::None
+    }
+}
+
 pub fn row_to_index(row: char) -> usize {
     row.to_digit(10).unwrap() as usize - 1
 }
@@ -149,3 +264,59 @@ pub fn row_to_index(row: char) -> usize {
 pub fn col_to_index(col: char) -> usize {
     col as usize - 97
 }
+
+// Serializes a board of player symbols row by row, using 'x' for the first player, 'o' for the
+// second, and '.' for empty squares, with rows separated by '/'.
+pub fn symbols_to_string(board: &[PlayerSymbol], columns: usize) -> String {
+    board
+        .chunks(columns)
+        .map(|row| row.iter().map(|s| match s {
+            PlayerSymbol::First => 'x',
+            PlayerSymbol::Second => 'o',
+            PlayerSymbol::Empty => '.',
+        }).collect::<String>())
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+// Parses a board of player symbols serialized by `symbols_to_string`, asserting that it has the
+// given dimensions.
+pub fn symbols_from_string(string: &str, rows: usize, columns: usize) -> Result<Vec<PlayerSymbol>, WebErr> {
+    let parsed_rows = string.split('/').collect::<Vec<&str>>();
+    if parsed_rows.len() != rows || parsed_rows.iter().any(|r| r.chars().count() != columns) {
+        return Err(WebErr::BadReq(format!("board {} is not {} rows of {} squares", string, rows, columns)));
+    }
+
+    parsed_rows
+        .iter()
+        .flat_map(|row| row.chars())
+        .map(|c| match c {
+            'x' => Ok(PlayerSymbol::First),
+            'o' => Ok(PlayerSymbol::Second),
+            '.' => Ok(PlayerSymbol::Empty),
+            _ => Err(WebErr::BadReq(format!("square {} is not one of 'x', 'o' or '.'", c))),
+        })
+        .collect()
+}
+
+// Splits a position into its space separated fields, asserting that there are `count` of them.
+pub fn position_fields<'a>(position: &'a str, count: usize, example: &str) -> Result<Vec<&'a str>, WebErr> {
+    let fields = position.split_whitespace().collect::<Vec<&str>>();
+    if fields.len() != count {
+        return Err(WebErr::BadReq(format!("position {} is not in the format \"{}\"", position, example)));
+    }
+    Ok(fields)
+}
+
+// Parses the side to move of a position, 'x' for the first player and 'o' for the second.
+pub fn side_from_str(side: &str) -> Result<bool, WebErr> {
+    match side {
+        "x" => Ok(true),
+        "o" => Ok(false),
+        _ => Err(WebErr::BadReq(format!("side to move {} is not \"x\" or \"o\"", side))),
+    }
+}
+
+pub fn side_to_str(first: bool) -> &'static str {
+    if first { "x" } else { "o" }
+}
@@ -1,72 +1,191 @@
-use super::ttt::{PlayerSymbol, check_board_status, row_to_index, col_to_index};
-use crate::models::general::MoveOutcome;
-
-
-// Validates a uttt move. A move is invalid if:
-// 1. It is not in the correct format ("a1b2")
-// 2. It has already been played
-// 3. The active board is not -1 (any open board) and it was not placed in the currently active board
-// 4. The active board is -1 and it was placed in an already full board
-pub fn validate_uttt_move(new_move: &str, moves: Vec<&str>, game_states: &Vec<MoveOutcome>, active_board: i32) -> bool {
-    if moves.contains(&new_move)
-        || !new_move.chars().nth(0).is_some_and(|c| matches!(c, 'a'..='c'))
-        || !new_move.chars().nth(1).is_some_and(|c| c.is_digit(10))
-        || !new_move.chars().nth(2).is_some_and(|c| matches!(c, 'a'..='c'))
-        || !new_move.chars().nth(3).is_some_and(|c| c.is_digit(10)) {
-        return false;
+use super::rules::GameRules;
+use super::ttt::{
+    PlayerSymbol, Coord, check_board_status, get_board_status, symbols_to_string, symbols_from_string,
+    position_fields, side_from_str, side_to_str,
+};
+use crate::common::WebErr;
+use crate::models::general::{GameKey, MoveOutcome};
+
+
+#[derive(Clone)]
+pub struct UTTTBoard {
+    board: Vec<Vec<PlayerSymbol>>,
+    board_states: Vec<MoveOutcome>,
+    active_board: i32,
+    first_to_move: bool,
+}
+
+impl Default for UTTTBoard {
+    fn default() -> Self {
+        UTTTBoard {
+            board: vec![vec![PlayerSymbol::Empty; 9]; 9],
+            board_states: vec![MoveOutcome::None; 9],
+            active_board: 4,
+            first_to_move: true,
+        }
+    }
+}
+
+impl GameRules for UTTTBoard {
+    // (outer board, inner square)
+    type Move = (Coord, Coord);
+
+    const KEY: GameKey = GameKey::UTTT;
+
+    // Parses a uttt position in the format "x......../.../.o....... b2 x": each inner board from
+    // a1 to c3 without row separators, separated by '/', followed by the active board (or '-' if
+    // any open board can be played) and the side to move.
+    fn from_position(position: &str) -> Result<Self, WebErr> {
+        let fields = position_fields(position, 3, "x......../.../.o....... b2 x")?;
+        let inner_boards = fields[0].split('/').collect::<Vec<&str>>();
+        if inner_boards.len() != 9 {
+            return Err(WebErr::BadReq(format!("position {} does not have 9 inner boards", position)));
+        }
+
+        let board = inner_boards
+            .iter()
+            .map(|b| symbols_from_string(b, 1, 9))
+            .collect::<Result<Vec<Vec<PlayerSymbol>>, WebErr>>()?;
+        let board_states = board.iter().map(|b| get_board_status(b, 3, 3, 3)).collect::<Vec<MoveOutcome>>();
+
+        let active_board = match fields[1] {
+            "-" => -1,
+            active => {
+                let coord = Coord::parse(active)?;
+                if board_states[coord.index()] != MoveOutcome::None {
+                    return Err(WebErr::BadReq(format!("active board {} has already been decided", coord)));
+                }
+                coord.index() as i32
+            },
+        };
+
+        let board = UTTTBoard {
+            board,
+            board_states,
+            active_board,
+            first_to_move: side_from_str(fields[2])?,
+        };
+        if get_board_status(&board.outer_board(), 3, 3, 3) != MoveOutcome::None
+            || board.board_states.iter().all(|s| *s != MoveOutcome::None)
+        {
+            return Err(WebErr::BadReq(format!("position {} has already been decided", position)));
+        }
+        Ok(board)
+    }
+
+    // Parses a uttt move in the format "a1b2" into outer board and inner square coordinates.
+    fn parse_move(&self, new_move: &str) -> Result<(Coord, Coord), WebErr> {
+        if new_move.len() != 4 || !new_move.is_char_boundary(2) {
+            return Err(WebErr::BadReq(format!("move {} is not in the format \"a1b2\"", new_move)));
+        }
+        Ok((Coord::parse(&new_move[..2])?, Coord::parse(&new_move[2..])?))
+    }
+
+    // Validates a uttt move. A move is invalid if:
+    // 1. It has already been played
+    // 2. It was placed in a board that has already been won or filled
+    // 3. The active board is not -1 (any open board) and it was not placed in the currently active board
+    fn check_move(&self, m: &(Coord, Coord)) -> Result<(), WebErr> {
+        let (outer, inner) = (m.0.index(), m.1.index());
+
+        if self.board[outer][inner] != PlayerSymbol::Empty {
+            return Err(WebErr::BadReq(format!("square {} on board {} is already taken", m.1, m.0)));
+        }
+        if self.board_states[outer] != MoveOutcome::None {
+            return Err(WebErr::BadReq(format!("board {} has already been decided", m.0)));
+        }
+        if self.active_board != -1 && outer != self.active_board as usize {
+            return Err(WebErr::BadReq(format!("move must be played on the active board, not {}", m.0)));
+        }
+        Ok(())
+    }
+
+    fn apply_move(&mut self, m: (Coord, Coord), is_first: bool) -> MoveOutcome {
+        let (outer, inner) = (m.0.index(), m.1.index());
+
+        // 1. Set the square on the inner board to the given player symbol.
+        self.board[outer][inner] = if is_first {
+            PlayerSymbol::First
+        } else {
+            PlayerSymbol::Second
+        };
+
+        // 2. Update the inner board status by running the ttt board check function on it.
+        // Do this before updating the active board in case the move points back to the
+        // same square and simultaneously wins that square.
+        // TODO: better move num calc?
+        let move_num = self.board[outer].iter().filter(|m| **m != PlayerSymbol::Empty).count();
+        self.board_states[outer] = check_board_status(inner, move_num, &self.board[outer], 3, 3, 3);
+
+        // 3. Finally, update the active board.
+        self.active_board = if self.board_states[inner] != MoveOutcome::None {
+            -1
+        } else {
+            inner as i32
+        };
+
+        self.first_to_move = !is_first;
+
+        let outer_move_num = self.board_states.iter().filter(|m| **m != MoveOutcome::None).count();
+        check_board_status(outer, outer_move_num, &self.outer_board(), 3, 3, 3)
     }
 
-    let outer = col_to_index(new_move.chars().nth(0).unwrap())
-        + row_to_index(new_move.chars().nth(1).unwrap()) * 3;
+    fn first_to_move(&self) -> bool {
+        self.first_to_move
+    }
+
+    fn move_list(&self) -> Vec<String> {
+        let mut moves = vec![];
+        for outer in 0..9 {
+            if self.board_states[outer] != MoveOutcome::None || self.active_board != -1 && outer != self.active_board as usize {
+                continue;
+            }
+            for inner in (0..9).filter(|i| self.board[outer][*i] == PlayerSymbol::Empty) {
+                moves.push(format!(
+                    "{}{}",
+                    Coord { col: outer % 3, row: outer / 3 },
+                    Coord { col: inner % 3, row: inner / 3 },
+                ));
+            }
+        }
+        moves
+    }
+
+    // Gets the board the next move must be played on, or `None` if any open board can be played.
+    fn active_board(&self) -> Option<usize> {
+        if self.active_board == -1 {
+            None
+        } else {
+            Some(self.active_board as usize)
+        }
+    }
+
+    // Serializes each inner board without row separators, separated by '/', followed by the
+    // active board (or '-' if any open board can be played) and the side to move.
+    fn serialize(&self) -> String {
+        let inner_boards = self.board.iter()
+            .map(|b| symbols_to_string(b, 9))
+            .collect::<Vec<String>>()
+            .join("/");
 
-    if active_board == -1 {
-        game_states[outer] == MoveOutcome::None
-    } else {
-        outer == active_board as usize
+        let active_board = if self.active_board == -1 {
+            "-".to_string()
+        } else {
+            let index = self.active_board as usize;
+            Coord { col: index % 3, row: index / 3 }.to_string()
+        };
+
+        format!("{} {} {}", inner_boards, active_board, side_to_str(self.first_to_move))
     }
 }
 
-pub fn process_uttt_move(
-    new_move: &str,
-    board: &mut Vec<Vec<PlayerSymbol>>,
-    board_states: &mut Vec<MoveOutcome>,
-    active_board: &mut i32,
-    is_first: bool
-) -> MoveOutcome {
-    let outer = col_to_index(new_move.chars().nth(0).unwrap())
-        + row_to_index(new_move.chars().nth(1).unwrap()) * 3;
-
-    let inner = col_to_index(new_move.chars().nth(2).unwrap())
-        + row_to_index(new_move.chars().nth(3).unwrap()) * 3;
-
-    // 1. Set the square on the inner board to the given player symbol.
-    board[outer][inner] = if is_first {
-        PlayerSymbol::First
-    } else {
-        PlayerSymbol::Second
-    };
-
-    // 2. Update the inner board status by running the ttt board check function on it.
-    // Do this before updating the active board in case the move points back to the
-    // same square and simultaneously wins that square.
-    // TODO: better move num calc?
-    let move_num = board[outer].iter().filter(|m| **m != PlayerSymbol::Empty).count();
-    board_states[outer] = check_board_status(inner, move_num, &board[outer], 3, 3, 3);
-
-    // 3. Finally, update the active board.
-    *active_board = if board_states[inner] != MoveOutcome::None {
-        -1
-    } else {
-        inner as i32
-    };
-
-    // Map game state vec to ttt symbols to check the status of the outer board
-    let outer_move_num = board_states.iter().filter(|m| **m != MoveOutcome::None).count();
-    let outer_board = board_states.iter().map(|m| match m {
-        MoveOutcome::FirstWin => PlayerSymbol::First,
-        MoveOutcome::SecondWin => PlayerSymbol::Second,
-        _ => PlayerSymbol::Empty
-    }).collect::<Vec<_>>();
-
-    check_board_status(outer, outer_move_num, &outer_board, 3, 3, 3)
+impl UTTTBoard {
+    // Maps the inner board states to ttt symbols to check the status of the outer board.
+    fn outer_board(&self) -> Vec<PlayerSymbol> {
+        self.board_states.iter().map(|m| match m {
+            MoveOutcome::FirstWin => PlayerSymbol::First,
+            MoveOutcome::SecondWin => PlayerSymbol::Second,
+            _ => PlayerSymbol::Empty
+        }).collect()
+    }
 }
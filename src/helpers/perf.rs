@@ -1,46 +1,73 @@
+use std::collections::{HashMap, HashSet};
 use actix_web::web;
 use prisma_client_rust::{not, or};
+use strum::IntoEnumIterator;
 
-use crate::{models::general::{Perfs, GamePerf}, common::WebErr};
+use crate::{models::general::{Perfs, GamePerf, GameKey, Speed, EndType}, common::WebErr};
 use crate::prisma::{perf, PrismaClient, game};
 
+// Rating deviation above which a rating is provisional
+pub const PROVISIONAL_RD: f64 = 110.0;
 
 pub async fn get_perfs_struct(client: &web::Data<PrismaClient>, perfs: Vec<perf::Data>) -> Result<Perfs, WebErr> {
-    let ttt = perfs.iter().find(|p| p.game_key == "ttt")
-        .ok_or(WebErr::NotFound(format!("ttt perf not found")))?;
-    let uttt = perfs.iter().find(|p| p.game_key == "uttt")
-        .ok_or(WebErr::NotFound(format!("uttt perf not found")))?;
-    let c4 = perfs.iter().find(|p| p.game_key == "c4")
-        .ok_or(WebErr::NotFound(format!("c4 perf not found")))?;
-    let pc = perfs.iter().find(|p| p.game_key == "pc")
-        .ok_or(WebErr::NotFound(format!("pc perf not found")))?;
-
-    Ok(Perfs {
-        ttt: ttt.to_game_perf(client).await?,
-        uttt: uttt.to_game_perf(client).await?,
-        c4: c4.to_game_perf(client).await?,
-        pc: pc.to_game_perf(client).await?,
-    })
+    let games = match perfs.first() {
+        Some(p) => count_finished_games(client, vec![p.username.clone()]).await?,
+        None => HashMap::new(),
+    };
+
+    let mut perfs_map = HashMap::new();
+    for k in GameKey::iter() {
+        let mut speeds_map = HashMap::new();
+        for speed in Speed::iter() {
+            let perf = perfs.iter().find(|p| p.game_key == k.to_string() && p.speed == speed.to_string())
+                .ok_or(WebErr::NotFound(format!("{} {} perf not found", k, speed)))?;
+            speeds_map.insert(speed, perf.to_game_perf(*games.get(&(perf.username.clone(), k.to_string(), speed)).unwrap_or(&0))?);
+        }
+        perfs_map.insert(k.to_string(), speeds_map);
+    }
+
+    Ok(Perfs(perfs_map))
 }
 
-impl perf::Data {
-    pub async fn to_game_perf(&self, client: &web::Data<PrismaClient>) -> Result<GamePerf, WebErr> {
-        let games = client
-            .game()
-            .find_many(vec![
-                or![
-                    game::first_username::equals(Some(self.username.clone())),
-                    game::second_username::equals(Some(self.username.clone()))
-                ],
-                not!(game::win_type::equals(None)),
-                game::game_key::equals(self.game_key.clone()),
-            ])
-            .exec()
-            .await
-            .or(Err(WebErr::Internal(format!("error fetching games for perf with id {}", self.game_key))))?;
+// counts the finished games of each of the given players in one query, by game key and speed pool.
+// aborted games never really started, so they aren't counted
+pub async fn count_finished_games(
+    client: &web::Data<PrismaClient>,
+    usernames: Vec<String>,
+) -> Result<HashMap<(String, String, Speed), i32>, WebErr> {
+    let games = client
+        .game()
+        .find_many(vec![
+            or![
+                game::first_username::in_vec(usernames.clone()),
+                game::second_username::in_vec(usernames.clone())
+            ],
+            not!(game::win_type::equals(None)),
+            not!(game::win_type::equals(Some(EndType::Abort.to_string()))),
+        ])
+        .exec()
+        .await
+        .or(Err(WebErr::Internal(format!("error fetching finished games to count"))))?;
 
+    let usernames: HashSet<String> = usernames.into_iter().collect();
+    let mut counts = HashMap::new();
+    for g in games {
+        let speed = g.get_speed();
+        for username in [&g.first_username, &g.second_username].into_iter().flatten() {
+            if usernames.contains(username) {
+                *counts.entry((username.clone(), g.game_key.clone(), speed)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    Ok(counts)
+}
+
+impl perf::Data {
+    // `games` is the number of finished games in this perf's pool, see `count_finished_games`
+    pub fn to_game_perf(&self, games: i32) -> Result<GamePerf, WebErr> {
         Ok(GamePerf {
-            games: games.len() as i32,
+            games,
             rating: self.rating,
             rd: self.rd,
             volatility: self.volatility,
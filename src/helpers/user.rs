@@ -4,101 +4,75 @@ use glicko_2::Tuning;
 use rand::Rng;
 use strum::IntoEnumIterator;
 
-use crate::models::general::{MatchPlayer, Profile, Country, Side, GameKey, GamePerf, ProfileGame, Player};
+use crate::models::general::{MatchPlayer, Profile, Country, Side, GameKey, GamePerf, ProfileGame, Player, Speed};
 use crate::models::res::UserResponse;
 use crate::models::req::CreateGameReq;
-use crate::prisma::{user, PrismaClient};
+use crate::prisma::{user, perf, PrismaClient};
 use crate::common::WebErr;
 use crate::helpers::perf::get_perfs_struct;
 use super::general::get_user_with_relations;
 
 
 impl user::Data {
-    // method to get provisional for game
-    pub fn get_provisional(&self, game_key: &str) -> Result<bool, WebErr> {
-        let perfs = self.perfs().or(Err(WebErr::Internal(format!("perfs not fetched"))))?;
+    // method to get the perf for a game's rating pool at the given speed
+    fn get_perf(&self, game_key: &str, speed: Speed) -> Result<&perf::Data, WebErr> {
+        self.perfs()
+            .or(Err(WebErr::Internal(format!("perfs not fetched"))))?
+            .iter().find(|p| p.game_key == game_key && p.speed == speed.to_string())
+            .ok_or(WebErr::Internal(format!("could not find {} perf for {}", speed, game_key)))
+    }
 
-        Ok(perfs.iter().find(|p| p.game_key == game_key)
-            .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-            .prov)
+    // method to get provisional for game
+    pub fn get_provisional(&self, game_key: &str, speed: Speed) -> Result<bool, WebErr> {
+        Ok(self.get_perf(game_key, speed)?.prov)
     }
 
     // method to get rating for game
-    pub fn get_rating(&self, game_key: &str) -> Result<f64, WebErr> {
-        let perfs = self.perfs().or(Err(WebErr::Internal(format!("perfs not fetched"))))?;
-
-        Ok(perfs.iter().find(|p| p.game_key == game_key)
-            .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-            .rating)
+    pub fn get_rating(&self, game_key: &str, speed: Speed) -> Result<f64, WebErr> {
+        Ok(self.get_perf(game_key, speed)?.rating)
     }
 
     // method to get rating deviation for game
-    pub fn get_rd(&self, game_key: &str) -> Result<f64, WebErr> {
-        let perfs = self.perfs().or(Err(WebErr::Internal(format!("perfs not fetched"))))?;
-
-        Ok(perfs.iter().find(|p| p.game_key == game_key)
-            .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-            .rd)
+    pub fn get_rd(&self, game_key: &str, speed: Speed) -> Result<f64, WebErr> {
+        Ok(self.get_perf(game_key, speed)?.rd)
     }
 
     // method to get volatility for game
-    pub fn get_volatility(&self, game_key: &str) -> Result<f64, WebErr> {
-        let perfs = self.perfs().or(Err(WebErr::Internal(format!("perfs not fetched"))))?;
-
-        Ok(perfs.iter().find(|p| p.game_key == game_key)
-            .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-            .volatility)
+    pub fn get_volatility(&self, game_key: &str, speed: Speed) -> Result<f64, WebErr> {
+        Ok(self.get_perf(game_key, speed)?.volatility)
     }
 
     // method to get change constraint for game
-    pub fn get_tau(&self, game_key: &str) -> Result<f64, WebErr> {
-        let perfs = self.perfs().or(Err(WebErr::Internal(format!("perfs not fetched"))))?;
-
-        Ok(perfs.iter().find(|p| p.game_key == game_key)
-            .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-            .tau)
+    pub fn get_tau(&self, game_key: &str, speed: Speed) -> Result<f64, WebErr> {
+        Ok(self.get_perf(game_key, speed)?.tau)
     }
 
     // method to get glicko 2 tuning struct for game
-    pub fn get_tuning(&self, game_key: &str) -> Result<Tuning, WebErr> {
-        let perfs = self.perfs().or(Err(WebErr::Internal(format!("perfs not fetched"))))?;
-
-        Ok(Tuning::new(
-            perfs.iter().find(|p| p.game_key == game_key)
-                .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-                .rating,
-            perfs.iter().find(|p| p.game_key == game_key)
-                .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-                .rd,
-            perfs.iter().find(|p| p.game_key == game_key)
-                .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-                .volatility,
-            perfs.iter().find(|p| p.game_key == game_key)
-                .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-                .tau,
-        ))
+    pub fn get_tuning(&self, game_key: &str, speed: Speed) -> Result<Tuning, WebErr> {
+        let perf = self.get_perf(game_key, speed)?;
+
+        Ok(Tuning::new(perf.rating, perf.rd, perf.volatility, perf.tau))
     }
 
     // method to get rating progression for game
-    pub fn get_prog(&self, game_key: &str) -> Result<String, WebErr> {
-        let perfs = self.perfs().or(Err(WebErr::Internal(format!("perfs not fetched"))))?;
-
-        Ok(perfs.iter().find(|p| p.game_key == game_key)
-            .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?
-            .prog.clone())
+    pub fn get_prog(&self, game_key: &str, speed: Speed) -> Result<String, WebErr> {
+        Ok(self.get_perf(game_key, speed)?.prog.clone())
     }
 
-    // method to add perfs if user is missing perfs for new games
+    // method to add perfs if user is missing perfs for new games or speeds
     pub async fn update_perfs(&mut self, client: &web::Data<PrismaClient>) -> Result<(), WebErr> {
         for k in GameKey::iter() {
-            if self.perfs().or(Err(WebErr::Internal(format!("perfs not fetched"))))?.iter()
-                .find(|p| p.game_key == k.to_string()).is_none()
-            {
+            for speed in Speed::iter() {
+                if self.get_perf(&k.to_string(), speed).is_ok() {
+                    continue;
+                }
+
                 client
                     .perf()
                     .create_unchecked(
                         self.username.clone(),
                         k.to_string(),
+                        speed.to_string(),
                         GamePerf::default().rating,
                         GamePerf::default().rd,
                         GamePerf::default().volatility,
@@ -117,12 +91,8 @@ impl user::Data {
         Ok(())
     }
 
-    // TODO: abstract this with `get_provisional` and such?
-    pub fn to_player(&self, game_key: &str) -> Result<Player, WebErr> {
-        let perf = self.perfs()
-            .or(Err(WebErr::Internal(format!("perfs not fetched"))))?
-            .iter().find(|p| p.game_key == game_key)
-            .ok_or(WebErr::Internal(format!("could not find perf for {}", game_key)))?;
+    pub fn to_player(&self, game_key: &str, speed: Speed) -> Result<Player, WebErr> {
+        let perf = self.get_perf(game_key, speed)?;
 
         Ok(Player {
             username: self.username.clone(),
@@ -136,8 +106,8 @@ impl user::Data {
 
         MatchPlayer {
             username: self.username.clone(),
-            provisional: self.get_provisional(game_key).unwrap(),
-            rating: self.get_rating(game_key).unwrap(),
+            provisional: self.get_provisional(game_key, req.get_speed()).unwrap(),
+            rating: self.get_rating(game_key, req.get_speed()).unwrap(),
             rating_min: req.rating_min,
             rating_max: req.rating_max,
             first: match req.side {
@@ -1,80 +1,211 @@
-use std::{collections::HashMap, time::{Duration, SystemTime}};
+use std::{collections::HashMap, env, future::Future, time::Duration};
 use actix_web::web::Data;
 use parking_lot::Mutex;
-use tokio::time::{interval_at, Instant};
+use tokio::{task::JoinHandle, time::{sleep_until, Instant}};
 
-use crate::{sse::Broadcaster, helpers::general::timeout_player, prisma::PrismaClient, player_stats::PlayerStats};
+use crate::{sse::Broadcaster, helpers::general::{timeout_player, abort_game, time_millis}, prisma::{PrismaClient, game}, player_stats::PlayerStats};
+use crate::lumber_mill::LumberMill;
+use crate::common::WebErr;
+use crate::models::general::GameStatus;
 
 
+// Time players get to make their first move before the game is aborted, unless set with the
+// FIRST_MOVE_SECS environment variable
+const DEFAULT_FIRST_MOVE_SECS: i32 = 30;
+// How long players of a game have to wait between giving each other more time
+const MORE_TIME_COOLDOWN: Duration = Duration::from_secs(10);
+
 pub struct Hourglass {
     hourglasses: HashMap<String, Glass>,
+    first_move_millis: i32,
+    // whether players can give each other more time in rated games, unless DISABLE_RATED_MORE_TIME is set
+    rated_more_time: bool,
+    // when more time was last given in each game
+    more_time_given: HashMap<String, Instant>,
+    client: Data<PrismaClient>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+    mill: Data<Mutex<LumberMill>>,
+    player_stats: Data<Mutex<PlayerStats>>,
 }
 
 pub struct Glass {
-    username: String,
-    millis: i32,
-    sys_time: SystemTime,
+    handle: JoinHandle<()>,
 }
 
 impl Hourglass {
-    pub fn create(
+    pub async fn create(
         client: Data<PrismaClient>,
         broadcaster: Data<Mutex<Broadcaster>>,
+        mill: Data<Mutex<LumberMill>>,
         player_stats: Data<Mutex<PlayerStats>>,
     ) -> Data<Mutex<Self>> {
-        let hourglass = Data::new(Mutex::new(Hourglass::new()));
+        let hourglass = Data::new(Mutex::new(Hourglass::new(client.clone(), broadcaster.clone(), mill, player_stats.clone())));
 
-        Hourglass::tick_hourglasses(hourglass.clone(), client, broadcaster, player_stats);
+        Hourglass::restore_hourglasses(&hourglass, &client, &broadcaster, &player_stats).await;
         hourglass
     }
 
-    fn new() -> Self {
+    fn new(
+        client: Data<PrismaClient>,
+        broadcaster: Data<Mutex<Broadcaster>>,
+        mill: Data<Mutex<LumberMill>>,
+        player_stats: Data<Mutex<PlayerStats>>,
+    ) -> Self {
         Hourglass {
             hourglasses: HashMap::new(),
+            first_move_millis: env::var("FIRST_MOVE_SECS")
+                .ok()
+                .and_then(|s| s.parse::<i32>().ok())
+                .unwrap_or(DEFAULT_FIRST_MOVE_SECS) * 1000,
+            rated_more_time: env::var("DISABLE_RATED_MORE_TIME").is_err(),
+            more_time_given: HashMap::new(),
+            client,
+            broadcaster,
+            mill,
+            player_stats,
         }
     }
 
-    fn tick_hourglasses(
-        hourglass: Data<Mutex<Self>>,
-        client: Data<PrismaClient>,
-        broadcaster: Data<Mutex<Broadcaster>>,
-        player_stats: Data<Mutex<PlayerStats>>,
+    // hourglasses only live in memory, so games still running from before a restart get theirs back
+    // from the last move time and remaining times stored with each game
+    async fn restore_hourglasses(
+        hourglass: &Data<Mutex<Self>>,
+        client: &Data<PrismaClient>,
+        broadcaster: &Data<Mutex<Broadcaster>>,
+        player_stats: &Data<Mutex<PlayerStats>>,
     ) {
-        actix_web::rt::spawn(async move {
-            let mut interval = interval_at(Instant::now(), Duration::from_millis(100));
-            loop {
-                interval.tick().await;
-                hourglass.lock().break_empty_hourglasses(&client, &broadcaster, &player_stats).await;
-            }
-        });
-    }
+        let games = match client
+            .game()
+            .find_many(vec![game::status::equals(GameStatus::Started.to_string())])
+            .exec()
+            .await
+        {
+            Ok(games) => games,
+            Err(_) => {
+                log::error!("error fetching started games to restore hourglasses");
+                return;
+            },
+        };
+
+        // the count of games being played is also lost on restart, and timing out a game lowers it
+        player_stats.lock().update_games(games.len() as i32, &broadcaster.lock());
+
+        for game in games {
+            let first_to_move = match game.first_to_move() {
+                Ok(first_to_move) => first_to_move,
+                Err(_) => {
+                    log::error!("error replaying moves to restore hourglass for game with id {}", game.id);
+                    continue;
+                },
+            };
 
-    async fn break_empty_hourglasses(&mut self, client: &Data<PrismaClient>, broadcaster: &Data<Mutex<Broadcaster>>, player_stats: &Data<Mutex<PlayerStats>>) {
-        let mut glasses_to_break: Vec<String> = vec![];
+            let username = if first_to_move {
+                game.first_username.clone().unwrap()
+            } else {
+                game.second_username.clone().unwrap()
+            };
+            let elapsed = (time_millis() - game.last_move_time) as i32;
 
-        for (game_id, glass) in &mut self.hourglasses {
-            if glass.sys_time.elapsed().unwrap().as_millis() as i32 > glass.millis {
-                timeout_player(&client, &broadcaster, &player_stats, game_id.to_string(), glass.username.clone()).await.ok();
-                glasses_to_break.push(game_id.to_string());
+            // a glass that has already run out breaks right away. games without moves don't store
+            // when they started, so their first player gets the full deadline again
+            if let Some(deadline) = game.move_deadline {
+                hourglass.lock().set_hourglass(game.id, username, (deadline - time_millis()) as i32);
+                continue;
+            }
+            match (game.num_moves(), game.first_time, game.second_time) {
+                (0, _, _) => hourglass.lock().set_first_move_deadline(game.id, username, 0),
+                (1, _, _) => hourglass.lock().set_first_move_deadline(game.id, username, elapsed),
+                (_, Some(first_time), Some(second_time)) => match game.get_millis_to_flag(
+                    if first_to_move { first_time } else { second_time },
+                    elapsed,
+                ) {
+                    Ok(millis) => hourglass.lock().set_hourglass(game.id, username, millis),
+                    Err(_) => log::error!("error reading clock type to restore hourglass for game with id {}", game.id),
+                },
+                _ => {},
             }
         }
-        for id in glasses_to_break {
-            self.hourglasses.remove(&id);
+    }
+
+    // arms the glass for a turn that starts now, e.g. when a game starts or a move is taken back
+    pub fn start_turn(&mut self, game: &game::Data) -> Result<(), WebErr> {
+        let first_to_move = game.first_to_move()?;
+        let (username, time) = if first_to_move {
+            (game.first_username.clone().unwrap(), game.first_time)
+        } else {
+            (game.second_username.clone().unwrap(), game.second_time)
+        };
+
+        match (game.move_deadline, time) {
+            (Some(deadline), _) => self.set_hourglass(game.id.clone(), username, (deadline - time_millis()) as i32),
+            _ if game.num_moves() < 2 => self.set_first_move_deadline(game.id.clone(), username, 0),
+            (None, Some(t)) => self.set_hourglass(game.id.clone(), username, game.get_millis_to_flag(t, 0)?),
+            (None, None) => self.break_hourglass(&game.id),
         }
+        Ok(())
     }
 
+    // arms a glass that times out the given player once their remaining time runs out, replacing
+    // (and cancelling) any glass already running for the game
     pub fn set_hourglass(&mut self, game_id: String, username: String, millis: i32) {
         log::debug!("game_id: {}, username: {}, millis: {}", game_id, username, millis);
-        self.hourglasses.entry(game_id)
-            .and_modify(|glass| {
-                glass.username = username.clone();
-                glass.millis = millis;
-                glass.sys_time = SystemTime::now();
-            })
-            .or_insert(Glass {
-                username,
-                millis,
-                sys_time: SystemTime::now(),
-            });
+        let client = self.client.clone();
+        let broadcaster = self.broadcaster.clone();
+        let player_stats = self.player_stats.clone();
+        let id = game_id.clone();
+
+        self.arm(game_id, millis, async move {
+            timeout_player(&client, &broadcaster, &player_stats, id, username).await.ok();
+        });
+    }
+
+    // arms a glass that aborts the game if the given player doesn't make their first move in time
+    pub fn set_first_move_deadline(&mut self, game_id: String, username: String, elapsed: i32) {
+        log::debug!("game_id: {}, username: {}, first move deadline", game_id, username);
+        let client = self.client.clone();
+        let broadcaster = self.broadcaster.clone();
+        let mill = self.mill.clone();
+        let player_stats = self.player_stats.clone();
+        let id = game_id.clone();
+
+        self.arm(game_id, self.first_move_millis - elapsed, async move {
+            abort_game(&client, &broadcaster, &mill, &player_stats, id, username).await.ok();
+        });
+    }
+
+    // checks whether more time can be given in a game right now, and if so starts its cooldown
+    pub fn try_give_more_time(&mut self, game_id: &str, rated: bool) -> Result<(), WebErr> {
+        if rated && !self.rated_more_time {
+            return Err(WebErr::Forbidden(format!("cannot give more time in rated games")));
+        }
+
+        self.more_time_given.retain(|_, given_at| given_at.elapsed() < MORE_TIME_COOLDOWN);
+        if self.more_time_given.contains_key(game_id) {
+            return Err(WebErr::Forbidden(format!("more time was given too recently in game with id {}", game_id)));
+        }
+
+        self.more_time_given.insert(game_id.to_string(), Instant::now());
+        Ok(())
+    }
+
+    // cancels the glass running for a game, if there is one
+    pub fn break_hourglass(&mut self, game_id: &str) {
+        if let Some(glass) = self.hourglasses.remove(game_id) {
+            glass.handle.abort();
+        }
+    }
+
+    // each glass sleeps on its own, so no lock is held while waiting or while ending the game
+    fn arm(&mut self, game_id: String, millis: i32, on_empty: impl Future<Output = ()> + 'static) {
+        self.break_hourglass(&game_id);
+        self.hourglasses.retain(|_, glass| !glass.handle.is_finished());
+
+        let deadline = Instant::now() + Duration::from_millis(millis.max(0) as u64);
+        let handle = actix_web::rt::spawn(async move {
+            sleep_until(deadline).await;
+            on_empty.await;
+        });
+
+        self.hourglasses.insert(game_id, Glass { handle });
     }
 }
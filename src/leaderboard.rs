@@ -0,0 +1,142 @@
+use std::{collections::{HashMap, HashSet}, str::FromStr, time::Duration};
+use actix_web::web::Data;
+use parking_lot::Mutex;
+use tokio::time::{interval_at, Instant};
+
+use crate::common::WebErr;
+use crate::helpers::perf::count_finished_games;
+use crate::models::general::{Country, GamePerf, Speed};
+use crate::models::res::LeaderboardEntry;
+use crate::prisma::{PrismaClient, perf, SortOrder};
+
+
+// Number of players on each leaderboard
+pub const LEADERBOARD_SIZE: usize = 100;
+// How often leaderboards are rebuilt from the stored perfs
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub struct Leaderboards {
+    // ranked players by game key and speed (or none, across speeds), holding everyone on the overall
+    // or a country's leaderboard
+    boards: HashMap<(String, Option<Speed>), Vec<LeaderboardEntry>>,
+}
+
+impl Leaderboards {
+    pub fn create(client: Data<PrismaClient>) -> Data<Mutex<Self>> {
+        let leaderboards = Data::new(Mutex::new(Leaderboards::new()));
+
+        Leaderboards::spawn_refresh(leaderboards.clone(), client);
+        leaderboards
+    }
+
+    fn new() -> Self {
+        Leaderboards {
+            boards: HashMap::new(),
+        }
+    }
+
+    fn spawn_refresh(me: Data<Mutex<Self>>, client: Data<PrismaClient>) {
+        actix_web::rt::spawn(async move {
+            let mut interval = interval_at(Instant::now(), REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                match Leaderboards::build(&client).await {
+                    Ok(boards) => me.lock().boards = boards,
+                    Err(e) => log::error!("error refreshing leaderboards: {:?}", e),
+                }
+            }
+        });
+    }
+
+    // ranks non-provisional, non-guest players by rating in each speed's pool, and across speeds by each
+    // player's best pool. anyone in the top of a leaderboard overall or for their country is kept, so
+    // filtered leaderboards can be served from the same list. game counts are filled in once everyone
+    // ranked is known, from a single query
+    async fn build(client: &Data<PrismaClient>) -> Result<HashMap<(String, Option<Speed>), Vec<LeaderboardEntry>>, WebErr> {
+        let perfs = client
+            .perf()
+            .find_many(vec![perf::prov::equals(false)])
+            .with(perf::user::fetch())
+            .order_by(perf::rating::order(SortOrder::Desc))
+            .exec()
+            .await
+            .or(Err(WebErr::Internal(format!("error fetching perfs for leaderboards"))))?;
+
+        let mut boards: HashMap<(String, Option<Speed>), Vec<LeaderboardEntry>> = HashMap::new();
+        let mut board_sizes: HashMap<(String, Option<Speed>, Option<String>), usize> = HashMap::new();
+        let mut ranked_across_speeds: HashSet<(String, String)> = HashSet::new();
+        let mut ranked: HashSet<String> = HashSet::new();
+
+        for p in perfs {
+            let user = p.user().or(Err(WebErr::Internal(format!("user not fetched for perf"))))?;
+            if user.guest {
+                continue;
+            }
+            let speed = Speed::from_str(&p.speed)?;
+
+            let mut keys = vec![Some(speed)];
+            if ranked_across_speeds.insert((p.game_key.clone(), p.username.clone())) {
+                keys.push(None);
+            }
+
+            let mut entry: Option<LeaderboardEntry> = None;
+            for key in keys {
+                let mut in_top = false;
+                for country in [None, Some(user.country.clone())] {
+                    let size = board_sizes.entry((p.game_key.clone(), key, country)).or_insert(0);
+                    *size += 1;
+                    in_top |= *size <= LEADERBOARD_SIZE;
+                }
+                if !in_top {
+                    continue;
+                }
+
+                if entry.is_none() {
+                    entry = Some(LeaderboardEntry {
+                        rank: 0,
+                        username: p.username.clone(),
+                        country: Country::from_str(&user.country)?,
+                        speed,
+                        rating: p.rating as i32,
+                        games: 0,
+                        trend: GamePerf::prog_from_str(&p.prog)?.iter().sum::<f64>() as i32,
+                    });
+                    ranked.insert(p.username.clone());
+                }
+                boards.entry((p.game_key.clone(), key)).or_insert(vec![]).push(entry.clone().unwrap());
+            }
+        }
+
+        let games = count_finished_games(client, ranked.into_iter().collect()).await?;
+        for ((game_key, _), entries) in boards.iter_mut() {
+            for entry in entries.iter_mut() {
+                entry.games = *games.get(&(entry.username.clone(), game_key.clone(), entry.speed)).unwrap_or(&0);
+            }
+        }
+
+        Ok(boards)
+    }
+
+    // gets the top players for a game, in one speed's pool or across speeds, ranked among players from
+    // the given country if there is one. at most `LEADERBOARD_SIZE` players are returned
+    pub fn get_leaderboard(
+        &self,
+        game_key: &str,
+        speed: Option<Speed>,
+        country: Option<Country>,
+        limit: Option<usize>,
+    ) -> Vec<LeaderboardEntry> {
+        self.boards
+            .get(&(game_key.to_string(), speed))
+            .unwrap_or(&vec![])
+            .iter()
+            .filter(|entry| country.as_ref().map_or(true, |c| entry.country == *c))
+            .take(limit.unwrap_or(LEADERBOARD_SIZE).min(LEADERBOARD_SIZE))
+            .enumerate()
+            .map(|(i, entry)| LeaderboardEntry {
+                rank: i + 1,
+                ..entry.clone()
+            })
+            .collect()
+    }
+}
@@ -7,6 +7,8 @@ pub mod sse;
 pub mod lumber_mill;
 pub mod hourglass;
 pub mod player_stats;
+pub mod leaderboard;
+pub mod rating_period;
 
 #[allow(warnings, unused)]
 pub mod prisma;
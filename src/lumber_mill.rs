@@ -3,24 +3,15 @@ use actix_web::web::Data;
 use parking_lot::Mutex;
 
 use crate::common::WebErr;
-use crate::helpers::moves::c4::{process_c4_move, validate_c4_move};
-use crate::helpers::moves::ttt::{PlayerSymbol, process_ttt_move, validate_ttt_move};
-use crate::helpers::moves::uttt::{process_uttt_move, validate_uttt_move};
+use crate::helpers::moves::rules::GameBoard;
 use crate::models::general::MoveOutcome;
 use crate::prisma::game;
 
-use self::GameBoard::{TTTBoard, UTTTBoard, C4Board};
-
 
 pub struct LumberMill {
-    pub boards: HashMap<String, GameBoard>,
-}
-
-#[derive(Clone)]
-pub enum GameBoard {
-    TTTBoard(Vec<PlayerSymbol>),
-    UTTTBoard(Vec<Vec<PlayerSymbol>>, Vec<MoveOutcome>, i32),
-    C4Board(Vec<PlayerSymbol>)
+    pub boards: HashMap<String, Box<dyn GameBoard>>,
+    // The move queued by the player waiting for their turn in each game, with their username
+    premoves: HashMap<String, (String, String)>,
 }
 
 impl LumberMill {
@@ -31,49 +22,27 @@ impl LumberMill {
     fn new() -> Self {
         LumberMill {
             boards: HashMap::new(),
+            premoves: HashMap::new(),
         }
     }
 
-    // Creates a new board populated from a given prisma game object.
+    // Creates a new board populated from a given prisma game object, starting from the game's
+    // custom start position if it has one.
     pub fn create_board_from_game(&mut self, game: &game::Data) -> Result<(), WebErr> {
-        let mut board = match game.game_key.as_str() {
-            "ttt" => TTTBoard(empty_ttt_board()),
-            "uttt" => UTTTBoard(
-                vec![empty_ttt_board(); 9],
-                vec![MoveOutcome::None; 9],
-                4,
-            ),
-            "c4" => C4Board(vec![PlayerSymbol::Empty; 42]),
-            _ => return Err(WebErr::BadReq(format!("game does not exist or is not supported")))
-        };
-
-        // Populate board with moves
-        let mut is_first = true;
-        for m in game.get_moves_vec_str() {
-            process_move(&mut board, m, is_first);
-            is_first = !is_first;
-        }
-
-        self.boards.insert(game.id.clone(), board);
+        self.boards.insert(game.id.clone(), game.get_board()?);
         Ok(())
     }
 
-    // Gets whether a move is valid given a game board.
-    pub fn validate_move(&mut self, game: &game::Data, new_move: &str) -> Result<bool, WebErr> {
+    // Asserts that a move is valid given a game board, returning a `WebErr::BadReq` with the reason
+    // if it is not.
+    pub fn validate_move(&mut self, game: &game::Data, new_move: &str) -> Result<(), WebErr> {
+        check_token(new_move)?;
+
         if !self.boards.contains_key(game.id.as_str()) {
             self.create_board_from_game(&game)?;
         }
 
-        let board = self.boards.get(game.id.as_str()).unwrap();
-        Ok(match board {
-            // TODO: use board checking instead of moves vec?
-            TTTBoard(_) =>
-                validate_ttt_move(new_move, game.get_moves_vec_str()),
-            UTTTBoard(_, game_states, active_board) =>
-                validate_uttt_move(new_move, game.get_moves_vec_str(), game_states, *active_board),
-            C4Board(_) =>
-                validate_c4_move(new_move)
-        })
+        self.boards.get(game.id.as_str()).unwrap().validate_move(new_move)
     }
 
     // Updates the given game's board with the provided move, checking the new board for victories
@@ -83,28 +52,48 @@ impl LumberMill {
             self.create_board_from_game(&game)?;
         }
 
-        Ok(process_move(
-            self.boards.get_mut(game.id.as_str()).unwrap(),
-            new_move,
-            is_first
-        ))
+        self.boards.get_mut(game.id.as_str()).unwrap().process_move(new_move, is_first)
     }
-}
 
-// Processes a move string, mutating the given game board with the new state
-// and returning the resultant `MoveOutcome`.
-fn process_move(board: &mut GameBoard, new_move: &str, is_first: bool) -> MoveOutcome {
-    match board {
-        TTTBoard(board) =>
-            process_ttt_move(new_move, board, is_first),
-        UTTTBoard(board, board_states, active_board) =>
-            process_uttt_move(new_move, board, board_states, active_board, is_first),
-        C4Board(board) =>
-            process_c4_move(new_move, board, is_first)
-        // "pc" => MoveOutcome::None,
+    // Gets the given game's cached board, creating it if it doesn't exist yet.
+    pub fn get_board(&mut self, game: &game::Data) -> Result<&dyn GameBoard, WebErr> {
+        if !self.boards.contains_key(game.id.as_str()) {
+            self.create_board_from_game(&game)?;
+        }
+
+        Ok(self.boards.get(game.id.as_str()).unwrap().as_ref())
+    }
+
+    // Queues a move for a player waiting for their turn, replacing any move they queued before. It is
+    // only checked to be a legal move once their turn comes.
+    pub fn set_premove(&mut self, game_id: &str, username: &str, new_move: &str) -> Result<(), WebErr> {
+        check_token(new_move)?;
+        self.premoves.insert(game_id.to_string(), (username.to_string(), new_move.to_string()));
+        Ok(())
+    }
+
+    // Removes and returns the move queued in a game, if it was queued by the given player.
+    pub fn take_premove(&mut self, game_id: &str, username: &str) -> Option<String> {
+        match self.premoves.get(game_id) {
+            Some((u, _)) if u == username => self.premoves.remove(game_id).map(|(_, m)| m),
+            _ => None,
+        }
+    }
+
+    pub fn clear_premove(&mut self, game_id: &str) {
+        self.premoves.remove(game_id);
+    }
+
+    // Gets whether the first player is to move on the given game's board.
+    pub fn first_to_move(&mut self, game: &game::Data) -> Result<bool, WebErr> {
+        Ok(self.get_board(game)?.first_to_move())
     }
 }
 
-fn empty_ttt_board() -> Vec<PlayerSymbol> {
-    vec![PlayerSymbol::Empty; 9]
+// Moves are stored space separated, so a move containing whitespace would be split apart
+fn check_token(new_move: &str) -> Result<(), WebErr> {
+    if new_move.is_empty() || new_move.contains(char::is_whitespace) {
+        return Err(WebErr::BadReq(format!("move {} must be a single token", new_move)));
+    }
+    Ok(())
 }
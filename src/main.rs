@@ -12,9 +12,11 @@ use aws_config::meta::region::RegionProviderChain;
 
 use game_backend::app_config::config_app;
 use game_backend::hourglass::Hourglass;
+use game_backend::leaderboard::Leaderboards;
 use game_backend::lumber_mill::LumberMill;
 use game_backend::player_stats::PlayerStats;
 use game_backend::prisma::PrismaClient;
+use game_backend::rating_period::spawn_rating_periods;
 use game_backend::sse::Broadcaster;
 
 
@@ -47,13 +49,16 @@ async fn main() -> std::io::Result<()> {
     let prisma_client = web::Data::new(PrismaClient::_builder().build().await.unwrap());
     let redis_store = RedisSessionStore::new(env::var("REDIS_URL").unwrap()).await.unwrap();
 
+    env::set_var("RUST_LOG", "debug");
+    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+
     let player_stats = PlayerStats::create();
     let broadcaster = Broadcaster::create(player_stats.clone());
     let lumber_mill = LumberMill::create();
-    let hourglass = Hourglass::create(prisma_client.clone(), broadcaster.clone(), player_stats.clone());
+    let hourglass = Hourglass::create(prisma_client.clone(), broadcaster.clone(), lumber_mill.clone(), player_stats.clone()).await;
+    let leaderboards = Leaderboards::create(prisma_client.clone());
+    spawn_rating_periods(prisma_client.clone());
 
-    env::set_var("RUST_LOG", "debug");
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
     log::info!("starting HTTP server at {}:{}", host, port);
 
     HttpServer::new(move || {
@@ -64,6 +69,7 @@ async fn main() -> std::io::Result<()> {
             .app_data(lumber_mill.clone())
             .app_data(player_stats.clone())
             .app_data(hourglass.clone())
+            .app_data(leaderboards.clone())
             .wrap(middleware::Logger::default())
             .wrap(
                 SessionMiddleware::builder(
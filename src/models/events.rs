@@ -28,6 +28,7 @@ pub enum GameEvent {
     GameStateEvent(GameStateEvent),
     GameFullEvent(GameFullEvent),
     RematchEvent(RematchEvent),
+    DisconnectEvent(DisconnectEvent),
 }
 
 impl GameEvent {
@@ -38,6 +39,7 @@ impl GameEvent {
             GameEvent::GameStateEvent(e) => serde_json::to_string(e).unwrap(),
             GameEvent::GameFullEvent(e) => serde_json::to_string(e).unwrap(),
             GameEvent::RematchEvent(e) => serde_json::to_string(e).unwrap(),
+            GameEvent::DisconnectEvent(e) => serde_json::to_string(e).unwrap(),
         }
     }
 }
@@ -142,8 +144,13 @@ pub struct GameStateEvent {
     pub status: GameStatus,
     pub end_type: Option<EndType>,
     pub draw_offer: Offer,
+    pub takeback_offer: Offer,
+    // number of moves taken back from the end of the game, before `moves` are added
+    pub undo: usize,
     pub frating_diff: Option<i32>,
     pub srating_diff: Option<i32>,
+    // server time the clocks were read at, in millis since the epoch
+    pub timestamp: i64,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -168,6 +175,16 @@ pub struct RematchEvent {
     pub id: Option<String>,
 }
 
+// sent when a player has been gone from a started game long enough for their opponent to claim it
+// (`left`), and again if they come back
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisconnectEvent {
+    pub r#type: GameEventType,
+    pub username: String,
+    pub left: bool,
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GameState {
@@ -177,8 +194,11 @@ pub struct GameState {
     pub status: GameStatus,
     pub end_type: Option<EndType>,
     pub draw_offer: Offer,
+    pub takeback_offer: Offer,
     pub frating_diff: Option<i32>,
     pub srating_diff: Option<i32>,
+    // when the player to move loses on time in a correspondence game, in millis since the epoch
+    pub move_deadline: Option<i64>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -289,6 +309,7 @@ pub enum GameEventType {
     GameState,
     GameFull,
     Rematch,
+    Disconnect,
 }
 
 #[derive(Deserialize, Serialize)]
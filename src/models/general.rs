@@ -1,5 +1,6 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use strum_macros::{EnumString, Display, EnumIter};
+use strum_macros::{EnumString, Display, EnumIter, EnumMessage};
 
 
 #[derive(Deserialize, Serialize)]
@@ -22,6 +23,15 @@ pub struct Player {
     pub rating: i32,
 }
 
+// Rating changes for a player if they win, draw or lose a game
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingPreview {
+    pub win: i32,
+    pub draw: i32,
+    pub loss: i32,
+}
+
 #[derive(Deserialize, Serialize, Copy, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Preferences {
@@ -91,17 +101,51 @@ pub struct GameType {
 pub struct TimeControl {
     pub initial: Option<i32>,
     pub increment: Option<i32>,
+    // days per move in correspondence games, which have no clock
+    pub days: Option<i32>,
+    pub clock_type: ClockType,
 }
 
-#[derive(Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Perfs {
-    pub ttt: GamePerf,
-    pub uttt: GamePerf,
-    pub c4: GamePerf,
-    pub pc: GamePerf,
+// Length of time rating history points are grouped by, keeping the last rating in each
+#[derive(Deserialize, Serialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryBucket {
+    Day,
+    Week,
+    Month,
+}
+
+// Rating pool a game belongs to, from how long its time control is expected to last
+#[derive(Deserialize, Serialize, Display, EnumString, EnumIter, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Speed {
+    Bullet,
+    Blitz,
+    Rapid,
+    Classical,
+    Correspondence,
+    Untimed,
+}
+
+// How the increment is applied to a player's clock for each move
+#[derive(Deserialize, Serialize, Display, EnumString, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ClockType {
+    // the increment is added after each move
+    #[default]
+    Fischer,
+    // the clock only starts running once the increment has passed
+    Delay,
+    // up to the increment of the time used is added back after each move
+    Bronstein,
+    // sudden death, the increment is ignored
+    None,
 }
 
+// Perfs keyed by game key string, then by speed
+#[derive(Deserialize, Serialize)]
+pub struct Perfs(pub HashMap<String, HashMap<Speed, GamePerf>>);
+
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GamePerf {
@@ -125,16 +169,31 @@ pub struct Profile {
     pub image_url: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, EnumIter)]
+#[derive(Deserialize, Serialize, Display, EnumString, EnumIter, EnumMessage, PartialEq, Eq, Hash, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 pub enum GameKey {
+    #[strum(message = "Tic-Tac-Toe")]
     TTT,
+    #[strum(message = "Ultimate Tic-Tac-Toe")]
     UTTT,
+    #[strum(message = "Connect 4")]
     C4,
+    #[strum(message = "Pokémon Chess")]
     PC,
+    #[strum(message = "Gomoku")]
+    Gomoku,
+    #[strum(message = "m,n,k-Game")]
+    MNK,
+    #[strum(message = "Othello")]
+    Othello,
+    #[strum(message = "Checkers")]
+    Checkers,
+    #[strum(message = "Dots and Boxes")]
+    DotsAndBoxes,
 }
 
-#[derive(Debug, Deserialize, Serialize, Display, EnumString)]
+#[derive(Debug, Deserialize, Serialize, Display, EnumString, PartialEq, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Country {
     Empty,
@@ -177,6 +236,7 @@ pub enum EndType {
     Timeout,
     Disconnect,
     Stalemate,
+    Abort,
 }
 
 #[derive(Deserialize, Serialize, Display, EnumString, PartialEq)]
@@ -4,7 +4,7 @@ use actix_multipart::form::text::Text;
 use serde::{Deserialize, Serialize};
 use crate::models::general::{Country, Preferences};
 
-use super::general::{Side, GameKey};
+use super::general::{Side, GameKey, ClockType, HistoryBucket, Speed};
 
 
 #[derive(Deserialize, Serialize)]
@@ -13,6 +13,9 @@ pub struct CreateGameReq {
     pub rated: bool,
     pub time: Option<i32>,
     pub increment: Option<i32>,
+    pub days: Option<i32>,
+    #[serde(default)]
+    pub clock_type: ClockType,
     pub side: Side,
     pub rating_min: i32,
     pub rating_max: i32,
@@ -53,10 +56,28 @@ pub struct ChallengeReq {
     pub rated: bool,
     pub time: Option<i32>,
     pub increment: Option<i32>,
+    pub days: Option<i32>,
+    #[serde(default)]
+    pub clock_type: ClockType,
     pub side: Side,
     pub start_pos: Option<String>,
 }
 
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingHistoryQuery {
+    pub speed: Option<Speed>,
+    pub bucket: Option<HistoryBucket>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardQuery {
+    pub speed: Option<Speed>,
+    pub country: Option<Country>,
+    pub limit: Option<usize>,
+}
+
 #[derive(Debug, MultipartForm)]
 pub struct ProfileReq {
     pub country: Text<Country>,
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::general::{GameType, TimeControl, Player, Profile, ProfileGame, Perfs, Side};
+use super::general::{GameType, TimeControl, Player, Profile, ProfileGame, Perfs, Side, RatingPreview, Country, Speed};
 use super::events::GameState;
 
 
@@ -30,6 +30,13 @@ pub struct GameResponse {
     pub second: Option<Player>,
 }
 
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegalMovesResponse {
+    pub moves: Vec<String>,
+    pub active_board: Option<usize>,
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LobbyResponse {
@@ -41,6 +48,7 @@ pub struct LobbyResponse {
     pub user: Player,
     pub game: GameType,
     pub time_control: TimeControl,
+    pub rating_preview: Option<RatingPreview>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -55,6 +63,29 @@ pub struct UserResponse {
     pub games: Vec<ProfileGame>,
 }
 
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingHistoryPoint {
+    pub rating: f64,
+    pub rd: Option<f64>,
+    pub speed: Speed,
+    pub game_id: String,
+    pub created_at: String,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub username: String,
+    pub country: Country,
+    pub speed: Speed,
+    pub rating: i32,
+    pub games: i32,
+    // rating change over the player's recent games
+    pub trend: i32,
+}
+
 #[derive(Deserialize, Serialize, Copy, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OkResponse {
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+use std::time::Duration;
+use actix_web::web::Data;
+use prisma_client_rust::not;
+use tokio::time::sleep;
+
+use crate::common::WebErr;
+use crate::helpers::general::{time_millis, DAY_MILLIS};
+use crate::helpers::perf::PROVISIONAL_RD;
+use crate::models::general::{EndType, GamePerf};
+use crate::prisma::{PrismaClient, SortOrder, game, perf, rating_period};
+
+
+// Length of a Glicko-2 rating period. Periods line up with the unix epoch, so restarts don't move them
+const RATING_PERIOD_MILLIS: i64 = 7 * DAY_MILLIS;
+// Scale between Glicko ratings and the Glicko-2 scale ratings are computed on
+const GLICKO2_SCALE: f64 = 173.7178;
+
+// Closes each rating period once it ends. Games are rated one by one as they end, so all that is left to do
+// is grow the rating deviation of pools players didn't play in during the period, as their rating gets less
+// certain. Closed periods are recorded, so any that ended while the server was down are closed in order when
+// it comes back. With none recorded yet, closing starts from the current period
+pub fn spawn_rating_periods(client: Data<PrismaClient>) {
+    actix_web::rt::spawn(async move {
+        loop {
+            let now = time_millis();
+            let period_start = match last_closed_period(&client).await {
+                Ok(Some(start)) => start + RATING_PERIOD_MILLIS,
+                Ok(None) => now / RATING_PERIOD_MILLIS * RATING_PERIOD_MILLIS,
+                Err(e) => {
+                    log::error!("error fetching last closed rating period: {:?}", e);
+                    now / RATING_PERIOD_MILLIS * RATING_PERIOD_MILLIS
+                },
+            };
+            let period_end = period_start + RATING_PERIOD_MILLIS;
+
+            // a period that failed to close is tried again once the current one ends
+            if period_end > now {
+                sleep(Duration::from_millis((period_end - now) as u64)).await;
+            } else if let Err(e) = close_rating_period(&client, period_start).await {
+                log::error!("error closing rating period: {:?}", e);
+                let next_end = (now / RATING_PERIOD_MILLIS + 1) * RATING_PERIOD_MILLIS;
+                sleep(Duration::from_millis((next_end - now) as u64)).await;
+            }
+        }
+    });
+}
+
+async fn last_closed_period(client: &Data<PrismaClient>) -> Result<Option<i64>, WebErr> {
+    Ok(client
+        .rating_period()
+        .find_first(vec![])
+        .order_by(rating_period::start::order(SortOrder::Desc))
+        .exec()
+        .await
+        .or(Err(WebErr::Internal(format!("error fetching last closed rating period"))))?
+        .map(|p| p.start))
+}
+
+// only rated games that ended within the period count as playing in it. the deviations grown and the record
+// of the period being closed are saved together, so a period is never closed twice
+async fn close_rating_period(client: &Data<PrismaClient>, period_start: i64) -> Result<(), WebErr> {
+    let games = client
+        .game()
+        .find_many(vec![
+            game::rated::equals(true),
+            not!(game::win_type::equals(Some(EndType::Abort.to_string()))),
+            game::end_time::gte(period_start),
+            game::end_time::lt(period_start + RATING_PERIOD_MILLIS),
+        ])
+        .exec()
+        .await
+        .or(Err(WebErr::Internal(format!("error fetching games played in rating period"))))?;
+
+    let active: HashSet<(String, String, String)> = games
+        .iter()
+        .flat_map(|g| [g.first_username.clone(), g.second_username.clone()].map(|u| (u, g.game_key.clone(), g.get_speed().to_string())))
+        .filter_map(|(username, game_key, speed)| username.map(|u| (u, game_key, speed)))
+        .collect();
+
+    let max_rd = GamePerf::default().rd;
+    let perfs = client
+        .perf()
+        .find_many(vec![perf::rd::lt(max_rd)])
+        .exec()
+        .await
+        .or(Err(WebErr::Internal(format!("error fetching perfs to close rating period"))))?;
+
+    let inactive: Vec<perf::Data> = perfs
+        .into_iter()
+        .filter(|p| !active.contains(&(p.username.clone(), p.game_key.clone(), p.speed.clone())))
+        .collect();
+
+    client
+        ._transaction()
+        .run(|tx| async move {
+            for p in inactive {
+                // with no games in a period, glicko-2 leaves the rating and volatility alone and only adds the
+                // volatility to the deviation (tau only constrains how volatility changes after games)
+                let phi = p.rd / GLICKO2_SCALE;
+                let rd = ((phi * phi + p.volatility * p.volatility).sqrt() * GLICKO2_SCALE).min(max_rd);
+
+                tx
+                    .perf()
+                    .update(
+                        perf::username_game_key_speed(p.username, p.game_key, p.speed),
+                        vec![
+                            perf::rd::set(rd),
+                            perf::prov::set(p.prov || rd > PROVISIONAL_RD),
+                        ],
+                    )
+                    .exec()
+                    .await?;
+            }
+
+            tx.rating_period().create(period_start, vec![]).exec().await
+        })
+        .await
+        .or(Err(WebErr::Internal(format!("error closing rating period starting at {}", period_start))))?;
+
+    Ok(())
+}
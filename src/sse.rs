@@ -9,7 +9,8 @@ use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::time::{interval_at, Instant};
 
 use crate::common::WebErr;
-use crate::models::events::{GameEvent, UserEvent, Event, LobbyEvent};
+use crate::helpers::general::time_millis;
+use crate::models::events::{GameEvent, UserEvent, Event, LobbyEvent, DisconnectEvent, GameEventType};
 use crate::player_stats::PlayerStats;
 
 
@@ -27,10 +28,47 @@ impl Stream for Client {
     }
 }
 
+// Time a player must be gone from a started game before their opponent can claim it
+const DISCONNECT_GRACE: Duration = Duration::from_secs(60);
+// Time after which a game nobody is connected to anymore stops being tracked
+const ABANDONED: Duration = Duration::from_secs(60 * 60);
+// Round trips longer than this are treated as a client that was asleep rather than network lag
+const MAX_ROUND_TRIP_MILLIS: i64 = 10_000;
+// Most lag compensation a player can have saved up, and how much of it they earn back each second
+const MAX_LAG_QUOTA_MILLIS: i32 = 2_000;
+const LAG_QUOTA_REFILL_PER_SEC: i32 = 50;
+
 pub struct Broadcaster {
     user_clients: HashMap<String, Vec<Sender<Bytes>>>,
     game_clients: HashMap<String, Vec<Sender<Bytes>>>,
     lobby_clients: Vec<Sender<Bytes>>,
+    // game streams opened by the players of each started game, by game id and username
+    player_clients: HashMap<String, HashMap<String, PlayerConnection>>,
+    // estimated round trip time to each connected user in milliseconds, from their answers to pings
+    lags: HashMap<String, i32>,
+    // lag compensation left to each user who has used some recently
+    lag_quotas: HashMap<String, LagQuota>,
+}
+
+pub struct LagQuota {
+    millis: i32,
+    updated_at: Instant,
+}
+
+pub struct PlayerConnection {
+    clients: Vec<Sender<Bytes>>,
+    // when the player's last user and game streams went away, if they have
+    left_at: Option<Instant>,
+    // whether the opponent has been told the player left
+    announced: bool,
+}
+
+impl LagQuota {
+    // the quota along with what has been earned back since it was last used
+    fn refilled(&self) -> i32 {
+        let earned = self.updated_at.elapsed().as_millis() as i64 * LAG_QUOTA_REFILL_PER_SEC as i64 / 1000;
+        (self.millis as i64 + earned).min(MAX_LAG_QUOTA_MILLIS as i64) as i32
+    }
 }
 
 impl Broadcaster {
@@ -46,6 +84,9 @@ impl Broadcaster {
             user_clients: HashMap::new(),
             game_clients: HashMap::new(),
             lobby_clients: Vec::new(),
+            player_clients: HashMap::new(),
+            lags: HashMap::new(),
+            lag_quotas: HashMap::new(),
         }
     }
 
@@ -61,10 +102,15 @@ impl Broadcaster {
     }
 
     fn remove_stale_clients(&mut self, player_stats: &Data<Mutex<PlayerStats>>) {
+        // user pings carry the time they were sent, which clients echo back to measure their lag
+        let user_ping = Bytes::from(format!("event: internal_status\ndata: ping {}\n\n", time_millis()));
         for vec in self.user_clients.values_mut() {
-            vec.retain(|x| x.clone().try_send(Bytes::from("event: internal_status\ndata: ping\n\n")).is_ok());
+            vec.retain(|x| x.clone().try_send(user_ping.clone()).is_ok());
         }
         self.user_clients.retain(|_, v| v.len() != 0);
+        let user_clients = &self.user_clients;
+        self.lags.retain(|username, _| user_clients.contains_key(username));
+        self.lag_quotas.retain(|_, quota| quota.refilled() < MAX_LAG_QUOTA_MILLIS);
         player_stats.lock().set_players(self.user_clients.keys().len() as i32, self);
 
         for vec in self.game_clients.values_mut() {
@@ -73,6 +119,94 @@ impl Broadcaster {
         self.game_clients.retain(|_, v| v.len() != 0);
 
         self.lobby_clients.retain(|x| x.clone().try_send(Bytes::from("event: internal_status\ndata: ping\n\n")).is_ok());
+
+        self.update_player_connections();
+    }
+
+    // Marks players of started games as gone once all their user and game streams have closed, and
+    // tells the game when they have been gone long enough for their opponent to claim it
+    fn update_player_connections(&mut self) {
+        let mut events: Vec<(String, DisconnectEvent)> = vec![];
+
+        for (game_id, players) in self.player_clients.iter_mut() {
+            for (username, connection) in players.iter_mut() {
+                connection.clients.retain(|x| !x.is_closed());
+                let connected = !connection.clients.is_empty() || self.user_clients.contains_key(username);
+
+                if connected {
+                    if connection.announced {
+                        events.push((game_id.clone(), DisconnectEvent {
+                            r#type: GameEventType::Disconnect,
+                            username: username.clone(),
+                            left: false,
+                        }));
+                    }
+                    connection.left_at = None;
+                    connection.announced = false;
+                    continue;
+                }
+
+                let left_at = *connection.left_at.get_or_insert(Instant::now());
+                if !connection.announced && left_at.elapsed() >= DISCONNECT_GRACE {
+                    events.push((game_id.clone(), DisconnectEvent {
+                        r#type: GameEventType::Disconnect,
+                        username: username.clone(),
+                        left: true,
+                    }));
+                    connection.announced = true;
+                }
+            }
+        }
+        self.player_clients.retain(|_, players| {
+            !players.values().all(|c| c.left_at.is_some_and(|left_at| left_at.elapsed() >= ABANDONED))
+        });
+
+        for (game_id, event) in events {
+            self.game_send(&game_id, GameEvent::DisconnectEvent(event));
+        }
+    }
+
+    // Gets whether a player has been gone from a started game for longer than the grace period
+    pub fn has_left_game(&self, game_id: &str, username: &str) -> bool {
+        self.player_clients
+            .get(game_id)
+            .and_then(|players| players.get(username))
+            .and_then(|connection| connection.left_at)
+            .is_some_and(|left_at| left_at.elapsed() >= DISCONNECT_GRACE)
+    }
+
+    // Stops tracking the players of a game once it has ended
+    pub fn remove_game_players(&mut self, game_id: &str) {
+        self.player_clients.remove(game_id);
+    }
+
+    // Updates a user's lag estimate with the round trip of a ping they answered, as a moving average
+    // so a single slow answer doesn't swing it
+    pub fn record_round_trip(&mut self, username: &str, sent_at: i64) {
+        let round_trip = time_millis() - sent_at;
+        if !(0..=MAX_ROUND_TRIP_MILLIS).contains(&round_trip) {
+            return;
+        }
+
+        self.lags.entry(username.to_string())
+            .and_modify(|lag| *lag = (*lag * 3 + round_trip as i32) / 4)
+            .or_insert(round_trip as i32);
+    }
+
+    // Takes lag compensation for a move that took `elapsed` milliseconds out of the user's quota, up to their
+    // estimated round trip time. Users who haven't answered a ping yet get none
+    pub fn take_lag_compensation(&mut self, username: &str, elapsed: i32) -> i32 {
+        let lag = self.lags.get(username).copied().unwrap_or(0);
+        let quota = self.lag_quotas.entry(username.to_string()).or_insert(LagQuota {
+            millis: MAX_LAG_QUOTA_MILLIS,
+            updated_at: Instant::now(),
+        });
+        quota.millis = quota.refilled();
+        quota.updated_at = Instant::now();
+
+        let compensation = lag.min(elapsed).min(quota.millis).max(0);
+        quota.millis -= compensation;
+        compensation
     }
 
     pub fn new_user_client(&mut self, username: String, player_stats: &Data<Mutex<PlayerStats>>) -> (Client, Sender<Bytes>) {
@@ -95,6 +229,20 @@ impl Broadcaster {
         (Client(rx), tx)
     }
 
+    // Marks a game client as belonging to one of the game's players, to track whether they are still there
+    pub fn add_player_client(&mut self, game_id: String, username: String, tx: Sender<Bytes>) {
+        self.player_clients.entry(game_id)
+            .or_insert(HashMap::new())
+            .entry(username)
+            .or_insert(PlayerConnection {
+                clients: vec![],
+                left_at: None,
+                announced: false,
+            })
+            .clients
+            .push(tx);
+    }
+
     pub fn new_lobby_client(&mut self) -> (Client, Sender<Bytes>) {
         let (tx, rx) = channel(100);
         self.lobby_clients.push(tx.clone());
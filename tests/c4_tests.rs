@@ -0,0 +1,28 @@
+use game_backend::helpers::moves::c4::C4Board;
+use game_backend::helpers::moves::rules::GameBoard;
+
+
+#[test]
+fn rejects_full_column_c4() {
+    let mut board = C4Board::default();
+    let mut is_first = true;
+    for _ in 0..6 {
+        assert!(board.validate_move("a").is_ok());
+        board.process_move("a", is_first).unwrap();
+        is_first = !is_first;
+    }
+
+    assert!(board.validate_move("a").is_err());
+    assert!(board.process_move("a", is_first).is_err());
+    assert!(board.validate_move("b").is_ok());
+}
+
+#[test]
+fn rejects_malformed_move_c4() {
+    let board = C4Board::default();
+
+    assert!(board.validate_move("").is_err());
+    assert!(board.validate_move("ab").is_err());
+    assert!(board.validate_move("h").is_err());
+    assert!(board.validate_move("A").is_err());
+}
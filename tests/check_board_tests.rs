@@ -122,3 +122,44 @@ fn anti_diag_doesnt_wrap_large_board() {
         MoveOutcome::None
     )
 }
+
+// [ _ _ _ x o o _ ]
+// [ _ _ _ _ x o _ ]
+// [ _ _ _ _ _ x o ]
+// [ _ _ _ _ _ _ X ]
+// [ _ _ _ _ _ _ _ ]
+// [ _ _ _ _ _ _ _ ]
+#[test]
+fn win_on_short_diagonal_c4() {
+    let mut board = vec![PlayerSymbol::Empty; 42];
+    for i in [3, 11, 19, 27] {
+        board[i] = PlayerSymbol::First;
+    }
+    for i in [4, 5, 12, 20] {
+        board[i] = PlayerSymbol::Second;
+    }
+
+    assert_eq!(
+        check_board_status(27, 8, &board, 6, 7, 4),
+        MoveOutcome::FirstWin,
+    )
+}
+
+// [ _ _ _ _ _ _ _ ]
+// [ _ _ _ _ x _ _ ]
+// [ _ _ _ _ _ x _ ]
+// [ _ _ _ _ _ _ X ]
+// [ _ _ _ _ _ _ _ ]
+// [ x _ _ _ _ _ _ ]
+#[test]
+fn diag_doesnt_wrap_c4() {
+    let mut board = vec![PlayerSymbol::Empty; 42];
+    for i in [11, 19, 27, 35] {
+        board[i] = PlayerSymbol::First;
+    }
+
+    assert_eq!(
+        check_board_status(27, 4, &board, 6, 7, 4),
+        MoveOutcome::None,
+    )
+}
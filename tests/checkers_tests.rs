@@ -0,0 +1,60 @@
+use game_backend::helpers::moves::checkers::CheckersBoard;
+use game_backend::helpers::moves::rules::{GameBoard, create_board};
+use game_backend::models::general::{GameKey, MoveOutcome};
+
+
+#[test]
+fn opening_moves_checkers() {
+    let board = CheckersBoard::default();
+
+    assert_eq!(board.legal_moves(true).len(), 7);
+    assert!(board.validate_move("c3-d4").is_ok());
+    assert!(board.validate_move("c3-c4").is_err());
+    assert!(board.validate_move("b2-c3").is_err());
+    assert!(board.validate_move("f6-e5").is_err());
+    assert!(board.validate_move("c3").is_err());
+}
+
+#[test]
+fn captures_are_forced_checkers() {
+    let mut board = CheckersBoard::default();
+    board.process_move("c3-d4", true).unwrap();
+    board.process_move("f6-e5", false).unwrap();
+
+    assert!(board.validate_move("a3-b4").is_err());
+    assert!(board.validate_move("d4-f6").is_ok());
+}
+
+// x must take two o men in one move, and the second jump ends on the king row. the crowned king
+// could jump the man on c7 next, but crowning ends the move
+#[test]
+fn multi_jump_and_kinging_checkers() {
+    let mut board = create_board(&GameKey::Checkers, Some("......../......../......../...x..../....o.../......../..o.o.../........ x 0")).unwrap();
+
+    assert!(board.validate_move("d4-f6").is_err());
+    assert!(board.validate_move("d4-f6-d8-b6").is_err());
+    assert_eq!(board.process_move("d4-f6-d8", true).unwrap(), MoveOutcome::None);
+    assert!(board.state().ends_with("/......../..o...../...X.... o 0"));
+    assert!(!board.first_to_move());
+}
+
+#[test]
+fn kings_reach_king_row_checkers() {
+    let mut board = create_board(&GameKey::Checkers, Some("......../......../......../......../......../.......o/x......./........ x 0")).unwrap();
+
+    assert_eq!(board.process_move("a7-b8", true).unwrap(), MoveOutcome::None);
+    assert!(board.state().ends_with(".X...... o 0"));
+}
+
+// Kings shuffling back and forth repeat the starting position a third time on the eighth ply
+#[test]
+fn threefold_repetition_checkers() {
+    let mut board = create_board(&GameKey::Checkers, Some("X......./......../......../......../......../......../......../.......O x 0")).unwrap();
+    let moves = ["a1-b2", "h8-g7", "b2-a1", "g7-h8"];
+
+    for m in moves.iter().chain(moves[..3].iter()) {
+        let is_first = board.first_to_move();
+        assert_eq!(board.process_move(m, is_first).unwrap(), MoveOutcome::None);
+    }
+    assert_eq!(board.process_move("g7-h8", false).unwrap(), MoveOutcome::Draw);
+}
@@ -0,0 +1,41 @@
+use game_backend::helpers::moves::dab::DABBoard;
+use game_backend::helpers::moves::rules::{GameBoard, create_board};
+use game_backend::models::general::{GameKey, MoveOutcome};
+
+
+#[test]
+fn rejects_lines_off_the_board_dab() {
+    let board = DABBoard::default();
+
+    assert_eq!(board.legal_moves().len(), 60);
+    assert!(board.validate_move("a1-b1").is_ok());
+    assert!(board.validate_move("b1-a1").is_ok());
+    assert!(board.validate_move("a1-b2").is_err());
+    assert!(board.validate_move("f6-g6").is_err());
+    assert!(board.validate_move("f6-f7").is_err());
+    assert!(board.validate_move("a1").is_err());
+}
+
+#[test]
+fn completing_a_box_earns_another_move_dab() {
+    let mut board = DABBoard::new(2, 2);
+
+    board.process_move("a1-b1", true).unwrap();
+    board.process_move("a1-a2", false).unwrap();
+    board.process_move("b1-b2", true).unwrap();
+    assert!(!board.first_to_move());
+    assert!(board.validate_move("b2-a2").is_ok());
+    assert!(board.validate_move("b1-a1").is_err());
+
+    board.process_move("b2-a2", false).unwrap();
+    assert!(!board.first_to_move());
+    assert_eq!(board.state(), "-./-./.. ||./... o./.. o");
+}
+
+// The second player completes both remaining boxes with one line, winning 3 boxes to 1
+#[test]
+fn most_boxes_wins_dab() {
+    let mut board = create_board(&GameKey::DotsAndBoxes, Some("--/--/-- |||/|.| xo/.. o")).unwrap();
+
+    assert_eq!(board.process_move("b2-b3", false).unwrap(), MoveOutcome::SecondWin);
+}
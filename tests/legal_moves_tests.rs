@@ -0,0 +1,41 @@
+use game_backend::helpers::moves::rules::create_board;
+use game_backend::models::general::GameKey;
+use strum::IntoEnumIterator;
+
+
+// Every listed move must be accepted by the board's own validation
+#[test]
+fn listed_moves_are_valid() {
+    for key in GameKey::iter() {
+        // m,n,k games have no default board, so they start from a small one
+        let start_pos = (key == GameKey::MNK).then_some("..../..../.... 3 x");
+        let board = create_board(&key, start_pos).unwrap();
+        let moves = board.legal_moves();
+
+        assert!(!moves.is_empty());
+        for m in moves {
+            assert!(board.validate_move(&m).is_ok(), "{} rejected its own move {}", key, m);
+        }
+    }
+}
+
+#[test]
+fn opening_move_counts() {
+    assert_eq!(create_board(&GameKey::TTT, None).unwrap().legal_moves().len(), 9);
+    assert_eq!(create_board(&GameKey::C4, None).unwrap().legal_moves().len(), 7);
+    assert_eq!(create_board(&GameKey::PC, None).unwrap().legal_moves().len(), 20);
+    assert_eq!(create_board(&GameKey::Othello, None).unwrap().legal_moves(), vec!["d3", "c4", "f5", "e6"]);
+}
+
+#[test]
+fn active_board_uttt() {
+    let mut board = create_board(&GameKey::UTTT, None).unwrap();
+
+    assert_eq!(board.active_board(), Some(4));
+    assert!(board.legal_moves().iter().all(|m| m.starts_with("b2")));
+
+    board.process_move("b2a1", true).unwrap();
+    assert_eq!(board.active_board(), Some(0));
+    assert_eq!(board.legal_moves().len(), 9);
+    assert_eq!(create_board(&GameKey::TTT, None).unwrap().active_board(), None);
+}
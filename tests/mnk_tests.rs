@@ -0,0 +1,74 @@
+use game_backend::helpers::moves::mnk::GomokuBoard;
+use game_backend::helpers::moves::rules::{GameBoard, create_board};
+use game_backend::models::general::{GameKey, MoveOutcome};
+
+
+fn play(board: &mut dyn GameBoard, moves: &[&str]) -> MoveOutcome {
+    let mut outcome = MoveOutcome::None;
+    for m in moves {
+        let is_first = board.first_to_move();
+        outcome = board.process_move(m, is_first).unwrap();
+    }
+    outcome
+}
+
+#[test]
+fn five_in_a_row_wins_gomoku() {
+    let mut board = GomokuBoard::default();
+
+    assert_eq!(play(&mut board, &["h8", "a1", "i9", "a2", "j10", "a3", "k11", "a4"]), MoveOutcome::None);
+    assert_eq!(play(&mut board, &["l12"]), MoveOutcome::FirstWin);
+}
+
+#[test]
+fn rejects_squares_off_the_board_gomoku() {
+    let board = GomokuBoard::default();
+
+    assert!(board.validate_move("p1").is_err());
+    assert!(board.validate_move("a16").is_err());
+    assert!(board.validate_move("a0").is_err());
+    assert!(board.validate_move("h08").is_err());
+    assert!(board.validate_move("o15").is_ok());
+}
+
+// Under the standard rule, six in a row does not win
+#[test]
+fn overline_only_wins_freestyle_gomoku() {
+    let moves = ["a1", "a15", "b1", "b15", "c1", "c15", "e1", "e15", "f1", "f15", "d1"];
+    let empty = vec!["..............."; 15].join("/");
+
+    let mut freestyle = create_board(&GameKey::Gomoku, Some(&format!("{} x", empty))).unwrap();
+    assert_eq!(play(freestyle.as_mut(), &moves), MoveOutcome::FirstWin);
+
+    let mut standard = create_board(&GameKey::Gomoku, Some(&format!("{} x standard", empty))).unwrap();
+    assert_eq!(play(standard.as_mut(), &moves), MoveOutcome::None);
+    assert!(standard.state().ends_with(" o standard"));
+}
+
+#[test]
+fn board_size_from_position_mnk() {
+    let mut board = create_board(&GameKey::MNK, Some("..../..../.... 3 o")).unwrap();
+
+    assert!(board.validate_move("e1").is_err());
+    assert!(board.validate_move("d3").is_ok());
+    assert_eq!(play(board.as_mut(), &["a1", "d3", "b2", "d2", "c3"]), MoveOutcome::SecondWin);
+    assert_eq!(board.state(), "o.../.o.x/..ox 3 x freestyle");
+}
+
+#[test]
+fn rejects_invalid_positions_mnk() {
+    assert!(create_board(&GameKey::MNK, Some("..../..../.... 5 x")).is_err());
+    assert!(create_board(&GameKey::MNK, Some("..../.../.... 3 x")).is_err());
+    assert!(create_board(&GameKey::MNK, Some("xxx./..../.... 3 o")).is_err());
+    assert!(create_board(&GameKey::MNK, Some("..../..../.... 3 x renju")).is_err());
+    assert!(create_board(&GameKey::MNK, None).is_err());
+}
+
+// k can run along the longer side of the board
+#[test]
+fn k_up_to_longer_side_mnk() {
+    let mut board = create_board(&GameKey::MNK, Some("..../..../.... 4 x")).unwrap();
+
+    assert_eq!(play(board.as_mut(), &["a1", "a2", "b1", "b2", "c1", "c2"]), MoveOutcome::None);
+    assert_eq!(play(board.as_mut(), &["d1"]), MoveOutcome::FirstWin);
+}
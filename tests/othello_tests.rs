@@ -0,0 +1,39 @@
+use game_backend::helpers::moves::othello::OthelloBoard;
+use game_backend::helpers::moves::rules::{GameBoard, create_board};
+use game_backend::models::general::{GameKey, MoveOutcome};
+
+
+#[test]
+fn placement_flips_flanked_discs_othello() {
+    let mut board = OthelloBoard::default();
+
+    assert_eq!(board.legal_moves(true), vec![19, 26, 37, 44]);
+    assert!(board.validate_move("a1").is_err());
+    assert!(board.validate_move("d4").is_err());
+    assert!(board.validate_move("pass").is_err());
+
+    board.process_move("d3", true).unwrap();
+    assert_eq!(
+        board.state(),
+        "......../......../...x..../...xx.../...xo.../......../......../........ o",
+    );
+}
+
+// o can't flank anything, so has to pass
+#[test]
+fn pass_only_without_placements_othello() {
+    let mut board = create_board(&GameKey::Othello, Some("xo....../......../......../......../......../......../......../.......o o")).unwrap();
+
+    assert!(board.validate_move("c1").is_err());
+    assert!(board.validate_move("pass").is_ok());
+    assert_eq!(board.process_move("pass", false).unwrap(), MoveOutcome::None);
+    assert!(board.validate_move("c1").is_ok());
+}
+
+// After x plays c1 there are no o discs left, so neither player can move
+#[test]
+fn disc_count_decides_when_neither_can_move_othello() {
+    let mut board = create_board(&GameKey::Othello, Some("xo....../......../......../......../......../......../......../........ x")).unwrap();
+
+    assert_eq!(board.process_move("c1", true).unwrap(), MoveOutcome::FirstWin);
+}
@@ -0,0 +1,70 @@
+use game_backend::helpers::moves::pc::PCBoard;
+use game_backend::helpers::moves::rules::GameBoard;
+use game_backend::models::general::MoveOutcome;
+
+
+fn play(board: &mut PCBoard, moves: &[&str]) -> MoveOutcome {
+    let mut outcome = MoveOutcome::None;
+    let mut is_first = true;
+    for m in moves {
+        board.validate_move(m).unwrap();
+        outcome = board.process_move(m, is_first).unwrap();
+        is_first = !is_first;
+    }
+    outcome
+}
+
+#[test]
+fn fools_mate_pc() {
+    let mut board = PCBoard::default();
+
+    assert_eq!(
+        play(&mut board, &["f2f3", "e7e5", "g2g4", "d8h4"]),
+        MoveOutcome::SecondWin,
+    )
+}
+
+#[test]
+fn must_resolve_check_pc() {
+    let mut board = PCBoard::default();
+    play(&mut board, &["e2e4", "f7f5", "d1h5"]);
+
+    assert!(board.in_check(false));
+    assert!(board.validate_move("e8f7").is_err());
+    assert!(board.validate_move("a7a6").is_err());
+    assert!(board.validate_move("g7g6").is_ok());
+}
+
+// Normal type pawns and ghost type knights have no effect on each other
+#[test]
+fn pawn_and_knight_cannot_capture_each_other_pc() {
+    let mut board = PCBoard::default();
+    play(&mut board, &["b1c3", "d7d5"]);
+    assert!(board.validate_move("c3d5").is_err());
+
+    play(&mut board, &["e2e4", "d5d4", "a2a3"]);
+    assert!(board.validate_move("d4c3").is_err());
+}
+
+// Psychic type bishops capturing each other is not very effective, so both are knocked out
+#[test]
+fn not_very_effective_capture_knocks_out_both_pc() {
+    let mut board = PCBoard::default();
+    play(&mut board, &["g2g3", "b7b6", "f1g2", "c8b7", "g2b7"]);
+
+    assert_eq!(
+        board.state(),
+        "rn1qkbnr/p1pppppp/1p6/8/8/6P1/PPPPPP1P/RNBQK1NR b KQkq - 0",
+    )
+}
+
+#[test]
+fn castling_moves_rook_pc() {
+    let mut board = PCBoard::default();
+    play(&mut board, &["e2e4", "e7e5", "g1f3", "b8c6", "f1c4", "g8f6", "e1g1"]);
+
+    assert_eq!(
+        board.state(),
+        "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5",
+    )
+}
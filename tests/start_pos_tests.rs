@@ -0,0 +1,70 @@
+use game_backend::helpers::moves::rules::create_board;
+use game_backend::models::general::{GameKey, MoveOutcome};
+
+
+#[test]
+fn seeds_board_and_side_to_move_ttt() {
+    let mut board = create_board(&GameKey::TTT, Some("xx./oo./... o")).unwrap();
+
+    assert!(!board.first_to_move());
+    assert_eq!(board.state(), "xx./oo./... o");
+    assert!(board.validate_move("a1").is_err());
+    assert_eq!(board.process_move("c2", false).unwrap(), MoveOutcome::SecondWin);
+}
+
+#[test]
+fn rejects_invalid_positions_ttt() {
+    assert!(create_board(&GameKey::TTT, Some("xxx/oo./... o")).is_err());
+    assert!(create_board(&GameKey::TTT, Some("xox/oxo/oxo x")).is_err());
+    assert!(create_board(&GameKey::TTT, Some("xx./oo./... y")).is_err());
+    assert!(create_board(&GameKey::TTT, Some("xx./oo. o")).is_err());
+    assert!(create_board(&GameKey::TTT, Some("xx./oo./...")).is_err());
+}
+
+#[test]
+fn rejects_floating_pieces_c4() {
+    assert!(create_board(&GameKey::C4, Some("......./...x.../......./......./......./....... o")).is_err());
+
+    let board = create_board(&GameKey::C4, Some("...x.../...o.../......./......./......./....... x")).unwrap();
+    assert!(board.first_to_move());
+    assert_eq!(board.state(), "...x.../...o.../......./......./......./....... x");
+}
+
+#[test]
+fn seeds_active_board_uttt() {
+    let position = "........./........./........./........./....x..../........./........./........./......... c3 o";
+    let board = create_board(&GameKey::UTTT, Some(position)).unwrap();
+
+    assert_eq!(board.state(), position);
+    assert!(board.validate_move("b2a1").is_err());
+    assert!(board.validate_move("c3a1").is_ok());
+}
+
+#[test]
+fn rejects_decided_active_board_uttt() {
+    let position = "xxx....../........./........./........./........./........./........./........./......... a1 o";
+
+    assert!(create_board(&GameKey::UTTT, Some(position)).is_err());
+    assert!(create_board(&GameKey::UTTT, Some(&position.replace("a1", "-"))).is_ok());
+}
+
+#[test]
+fn seeds_board_from_fen_pc() {
+    let position = "4k3/8/8/8/8/8/4P3/4K3 b - - 3";
+    let board = create_board(&GameKey::PC, Some(position)).unwrap();
+
+    assert!(!board.first_to_move());
+    assert_eq!(board.state(), position);
+    assert!(board.validate_move("e8d8").is_ok());
+    assert!(board.validate_move("e1d1").is_err());
+}
+
+#[test]
+fn rejects_invalid_positions_pc() {
+    // Missing second king
+    assert!(create_board(&GameKey::PC, Some("8/8/8/8/8/8/4P3/4K3 w - - 0")).is_err());
+    // Pawn on the last rank
+    assert!(create_board(&GameKey::PC, Some("3Pk3/8/8/8/8/8/8/4K3 w - - 0")).is_err());
+    // Castling without a rook
+    assert!(create_board(&GameKey::PC, Some("4k3/8/8/8/8/8/4P3/4K3 w K - 0")).is_err());
+}
@@ -0,0 +1,54 @@
+use game_backend::helpers::moves::rules::GameBoard;
+use game_backend::helpers::moves::ttt::TTTBoard;
+use game_backend::helpers::moves::uttt::UTTTBoard;
+
+
+#[test]
+fn rejects_out_of_range_squares_ttt() {
+    let board = TTTBoard::default();
+
+    assert!(board.validate_move("a0").is_err());
+    assert!(board.validate_move("a9").is_err());
+    assert!(board.validate_move("d1").is_err());
+    assert!(board.validate_move("a1b").is_err());
+    assert!(board.validate_move("c3").is_ok());
+}
+
+#[test]
+fn rejects_taken_square_ttt() {
+    let mut board = TTTBoard::default();
+    board.process_move("b2", true).unwrap();
+
+    assert!(board.validate_move("b2").is_err());
+    assert!(board.process_move("b2", false).is_err());
+}
+
+#[test]
+fn rejects_move_outside_active_board_uttt() {
+    let mut board = UTTTBoard::default();
+
+    // The first move must be played on the center board
+    assert!(board.validate_move("a1a1").is_err());
+    board.process_move("b2a1", true).unwrap();
+
+    // The next move is sent to the a1 board
+    assert!(board.validate_move("b2b2").is_err());
+    assert!(board.validate_move("a1b2").is_ok());
+    assert!(board.validate_move("a1a4").is_err());
+}
+
+#[test]
+fn rejects_move_on_decided_board_uttt() {
+    let mut board = UTTTBoard::default();
+    let mut is_first = true;
+
+    // First wins the a1 board along its second row, with second always sending play back to a1
+    for m in ["b2c3", "c3a1", "a1a2", "a2a1", "a1b2", "b2a1", "a1c2", "c2a1"] {
+        board.process_move(m, is_first).unwrap();
+        is_first = !is_first;
+    }
+
+    // Play is sent to the won a1 board, so any open board may be chosen except a1
+    assert!(board.validate_move("a1a3").is_err());
+    assert!(board.validate_move("c3b2").is_ok());
+}